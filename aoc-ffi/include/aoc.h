@@ -0,0 +1,58 @@
+#ifndef AOC_H
+#define AOC_H
+
+/* Generated by cbindgen from src/lib.rs, don't edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Result of every call into the library
+typedef enum AocStatus {
+  AOC_STATUS_OK = 0,
+  // `input` (with a non-zero `len`) or `out_buf` was null
+  AOC_STATUS_NULL_POINTER = 1,
+  AOC_STATUS_UNKNOWN_DAY = 2,
+  AOC_STATUS_UNKNOWN_PART = 3,
+  AOC_STATUS_INVALID_UTF8 = 4,
+  // The answer contained a NUL byte and can't be returned as a C string
+  AOC_STATUS_INVALID_ANSWER = 5,
+  // The solver panicked, usually because the input couldn't be parsed
+  AOC_STATUS_SOLVER_PANICKED = 6,
+} AocStatus;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Solves `part` of `day` for the `len` bytes of UTF-8 at `input`.
+//
+// On success `*out_buf` points to a NUL-terminated answer that must be
+// released with `aoc_free_string`, on failure it is set to null.
+//
+// # Safety
+//
+// `input` must point to `len` readable bytes (or be null when `len` is 0) and
+// `out_buf` must be null or point to writable storage for a pointer.
+enum AocStatus aoc_solve(uint32_t day,
+                         uint32_t part,
+                         const uint8_t *input,
+                         uintptr_t len,
+                         char **out_buf);
+
+// Releases an answer returned by `aoc_solve`, null is ignored.
+//
+// # Safety
+//
+// `answer` must be null or a pointer from `aoc_solve` that hasn't been freed yet.
+void aoc_free_string(char *answer);
+
+// Returns a static, NUL-terminated description of `status`.
+const char *aoc_status_message(enum AocStatus status);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* AOC_H */
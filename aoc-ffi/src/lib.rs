@@ -0,0 +1,196 @@
+//! C-ABI over all solvers, for calling them from C and C++ test harnesses.
+//!
+//! The header `include/aoc.h` is regenerated by the build script.
+
+use std::{
+    ffi::{c_char, CString},
+    panic, ptr, slice, str,
+};
+
+type SolveFn = fn(&str) -> String;
+
+/// Result of every call into the library
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AocStatus {
+    Ok = 0,
+    /// `input` (with a non-zero `len`) or `out_buf` was null
+    NullPointer = 1,
+    UnknownDay = 2,
+    UnknownPart = 3,
+    InvalidUtf8 = 4,
+    /// The answer contained a NUL byte and can't be returned as a C string
+    InvalidAnswer = 5,
+    /// The solver panicked, usually because the input couldn't be parsed
+    SolverPanicked = 6,
+}
+
+fn solver(day: u32) -> Option<(SolveFn, SolveFn)> {
+    let solvers: (SolveFn, SolveFn) = match day {
+        1 => (day01::process_part1, day01::process_part2),
+        2 => (day02::process_part1, day02::process_part2),
+        3 => (day03::process_part1, day03::process_part2),
+        4 => (day04::process_part1, day04::process_part2),
+        5 => (day05::process_part1, day05::process_part2),
+        6 => (day06::process_part1, day06::process_part2),
+        7 => (day07::process_part1, day07::process_part2),
+        8 => (day08::process_part1, day08::process_part2),
+        9 => (day09::process_part1, day09::process_part2),
+        10 => (day10::process_part1, day10::process_part2),
+        11 => (day11::process_part1, day11::process_part2),
+        12 => (day12::process_part1, day12::process_part2),
+        13 => (day13::process_part1, day13::process_part2),
+        14 => (day14::process_part1, day14::process_part2),
+        15 => (day15::process_part1, day15::process_part2),
+        16 => (day16::process_part1, day16::process_part2),
+        17 => (day17::process_part1, day17::process_part2),
+        18 => (day18::process_part1, day18::process_part2),
+        19 => (day19::process_part1, day19::process_part2),
+        20 => (day20::process_part1, day20::process_part2),
+        21 => (day21::process_part1, day21::process_part2),
+        22 => (day22::process_part1, day22::process_part2),
+        23 => (day23::process_part1, day23::process_part2),
+        24 => (day24::process_part1, day24::process_part2),
+        25 => (day25::process_part1, day25::process_part2),
+        _ => return None,
+    };
+    Some(solvers)
+}
+
+/// Solves `part` of `day` for the `len` bytes of UTF-8 at `input`.
+///
+/// On success `*out_buf` points to a NUL-terminated answer that must be
+/// released with `aoc_free_string`, on failure it is set to null.
+///
+/// # Safety
+///
+/// `input` must point to `len` readable bytes (or be null when `len` is 0) and
+/// `out_buf` must be null or point to writable storage for a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve(
+    day: u32,
+    part: u32,
+    input: *const u8,
+    len: usize,
+    out_buf: *mut *mut c_char,
+) -> AocStatus {
+    if out_buf.is_null() {
+        return AocStatus::NullPointer;
+    }
+    *out_buf = ptr::null_mut();
+    match solve(day, part, input, len) {
+        Ok(answer) => {
+            *out_buf = answer.into_raw();
+            AocStatus::Ok
+        }
+        Err(status) => status,
+    }
+}
+
+unsafe fn solve(day: u32, part: u32, input: *const u8, len: usize) -> Result<CString, AocStatus> {
+    let (part1, part2) = solver(day).ok_or(AocStatus::UnknownDay)?;
+    let solve = match part {
+        1 => part1,
+        2 => part2,
+        _ => return Err(AocStatus::UnknownPart),
+    };
+    let bytes = if len == 0 {
+        &[][..]
+    } else if input.is_null() {
+        return Err(AocStatus::NullPointer);
+    } else {
+        slice::from_raw_parts(input, len)
+    };
+    let input = str::from_utf8(bytes).map_err(|_| AocStatus::InvalidUtf8)?;
+    let answer = panic::catch_unwind(|| solve(input)).map_err(|_| AocStatus::SolverPanicked)?;
+    CString::new(answer).map_err(|_| AocStatus::InvalidAnswer)
+}
+
+/// Releases an answer returned by `aoc_solve`, null is ignored.
+///
+/// # Safety
+///
+/// `answer` must be null or a pointer from `aoc_solve` that hasn't been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_free_string(answer: *mut c_char) {
+    if !answer.is_null() {
+        drop(CString::from_raw(answer));
+    }
+}
+
+/// Returns a static, NUL-terminated description of `status`.
+#[no_mangle]
+pub extern "C" fn aoc_status_message(status: AocStatus) -> *const c_char {
+    let message: &'static [u8] = match status {
+        AocStatus::Ok => b"ok\0",
+        AocStatus::NullPointer => b"null pointer\0",
+        AocStatus::UnknownDay => b"unknown day\0",
+        AocStatus::UnknownPart => b"unknown part\0",
+        AocStatus::InvalidUtf8 => b"input is not valid UTF-8\0",
+        AocStatus::InvalidAnswer => b"answer contains a NUL byte\0",
+        AocStatus::SolverPanicked => b"solver panicked\0",
+    };
+    message.as_ptr() as *const c_char
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    fn call(day: u32, part: u32, input: &str) -> (AocStatus, Option<String>) {
+        let mut out = ptr::null_mut();
+        unsafe {
+            let status = aoc_solve(day, part, input.as_ptr(), input.len(), &mut out);
+            if out.is_null() {
+                return (status, None);
+            }
+            let answer = CStr::from_ptr(out).to_str().unwrap().to_string();
+            aoc_free_string(out);
+            (status, Some(answer))
+        }
+    }
+
+    #[test]
+    fn solves() {
+        assert_eq!(
+            call(6, 1, "3,4,3,1,2"),
+            (AocStatus::Ok, Some("5934".into()))
+        );
+        assert_eq!(
+            call(7, 2, "16,1,2,0,4,2,7,1,2,14"),
+            (AocStatus::Ok, Some("168".into()))
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(call(0, 1, "1"), (AocStatus::UnknownDay, None));
+        assert_eq!(call(26, 1, "1"), (AocStatus::UnknownDay, None));
+        assert_eq!(call(6, 3, "1"), (AocStatus::UnknownPart, None));
+        assert_eq!(call(16, 1, "not hex"), (AocStatus::SolverPanicked, None));
+        unsafe {
+            let bytes = [0xff, 0xfe];
+            let mut out = ptr::null_mut();
+            assert_eq!(
+                aoc_solve(6, 1, bytes.as_ptr(), bytes.len(), &mut out),
+                AocStatus::InvalidUtf8
+            );
+            assert_eq!(
+                aoc_solve(6, 1, ptr::null(), 5, &mut out),
+                AocStatus::NullPointer
+            );
+            assert_eq!(
+                aoc_solve(6, 1, bytes.as_ptr(), 0, ptr::null_mut()),
+                AocStatus::NullPointer
+            );
+            aoc_free_string(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn messages() {
+        let message = unsafe { CStr::from_ptr(aoc_status_message(AocStatus::UnknownDay)) };
+        assert_eq!(message.to_str().unwrap(), "unknown day");
+    }
+}
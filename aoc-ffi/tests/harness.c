@@ -0,0 +1,46 @@
+#include <stdio.h>
+#include <string.h>
+
+#include "aoc.h"
+
+static int failures = 0;
+
+static void expect_answer(uint32_t day, uint32_t part, const char *input, const char *expected) {
+    char *answer = NULL;
+    AocStatus status = aoc_solve(day, part, (const uint8_t *)input, strlen(input), &answer);
+    if (status != AOC_STATUS_OK || answer == NULL || strcmp(answer, expected) != 0) {
+        fprintf(stderr, "day %u part %u: expected %s, got %s (%s)\n", day, part, expected,
+                answer ? answer : "NULL", aoc_status_message(status));
+        failures++;
+    }
+    aoc_free_string(answer);
+}
+
+static void expect_status(uint32_t day, uint32_t part, const char *input, AocStatus expected) {
+    char *answer = NULL;
+    AocStatus status = aoc_solve(day, part, (const uint8_t *)input, strlen(input), &answer);
+    if (status != expected || answer != NULL) {
+        fprintf(stderr, "day %u part %u: expected status %s, got %s\n", day, part,
+                aoc_status_message(expected), aoc_status_message(status));
+        failures++;
+    }
+    aoc_free_string(answer);
+}
+
+int main(void) {
+    expect_answer(1, 1, "199\n200\n208\n210\n200\n207\n240\n269\n260\n263", "7");
+    expect_answer(6, 2, "3,4,3,1,2", "26984457539");
+    expect_answer(16, 1, "8A004A801A8002F478", "16");
+    expect_answer(17, 2, "target area: x=20..30, y=-10..-5", "112");
+
+    expect_status(26, 1, "", AOC_STATUS_UNKNOWN_DAY);
+    expect_status(6, 3, "3,4,3,1,2", AOC_STATUS_UNKNOWN_PART);
+    expect_status(16, 2, "XYZ", AOC_STATUS_SOLVER_PANICKED);
+    expect_status(6, 1, "\xff\xfe", AOC_STATUS_INVALID_UTF8);
+    if (aoc_solve(6, 1, NULL, 3, NULL) != AOC_STATUS_NULL_POINTER) {
+        fprintf(stderr, "null out_buf wasn't rejected\n");
+        failures++;
+    }
+
+    return failures == 0 ? 0 : 1;
+}
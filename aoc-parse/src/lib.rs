@@ -0,0 +1,138 @@
+//! nom combinators shared by the day crates.
+//!
+//! Number parsers fail with [`ErrorKind::TooLarge`] when the digits don't fit
+//! the requested type, so a malformed input is reported as a parse error
+//! instead of a panic.
+
+use std::str::FromStr;
+
+use nom::{
+    character::complete::{char as nomchar, digit1, newline, one_of, satisfy},
+    combinator::{map, opt, recognize},
+    error::{Error, ErrorKind},
+    multi::{many1, separated_list1},
+    sequence::pair,
+    IResult, Parser,
+};
+
+/// Integer types that [`unsigned`] can parse
+pub trait Integer: FromStr + Copy {}
+
+/// Integer types that [`signed`] can parse
+pub trait Signed: Integer {}
+
+macro_rules! integers {
+    ($($t:ty),*) => {
+        $(impl Integer for $t {})*
+    };
+}
+
+macro_rules! signed {
+    ($($t:ty),*) => {
+        $(impl Signed for $t {})*
+    };
+}
+
+integers!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+signed!(i8, i16, i32, i64, i128, isize);
+
+fn number<'a, T: Integer>(input: &'a str, digits: &'a str, rest: &'a str) -> IResult<&'a str, T> {
+    match digits.parse() {
+        Ok(n) => Ok((rest, n)),
+        Err(_) => Err(nom::Err::Error(Error::new(input, ErrorKind::TooLarge))),
+    }
+}
+
+/// Parses a run of digits into any integer type
+pub fn unsigned<T: Integer>(input: &str) -> IResult<&str, T> {
+    let (rest, digits) = digit1(input)?;
+    number(input, digits, rest)
+}
+
+/// Parses a run of digits with an optional leading `+` or `-`
+pub fn signed<T: Signed>(input: &str) -> IResult<&str, T> {
+    let (rest, digits) = recognize(pair(opt(one_of("+-")), digit1))(input)?;
+    number(input, digits, rest)
+}
+
+/// One or more `p`, one per line
+pub fn lines_of<'a, O, F>(p: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    F: Parser<&'a str, O, Error<&'a str>>,
+{
+    separated_list1(newline, p)
+}
+
+/// One or more `p`, separated by empty lines
+pub fn blank_line_separated<'a, O, F>(p: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    F: Parser<&'a str, O, Error<&'a str>>,
+{
+    separated_list1(pair(newline, newline), p)
+}
+
+/// One or more `p`, separated by commas
+pub fn comma_list<'a, O, F>(p: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    F: Parser<&'a str, O, Error<&'a str>>,
+{
+    separated_list1(nomchar(','), p)
+}
+
+/// Rows of single digit values, one row per line
+pub fn digit_grid<T: From<u8>>(input: &str) -> IResult<&str, Vec<Vec<T>>> {
+    let digit = map(satisfy(|c| c.is_ascii_digit()), |c| T::from(c as u8 - b'0'));
+    lines_of(many1(digit))(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use nom::character::complete::alpha1;
+
+    use super::*;
+
+    #[test]
+    fn numbers() {
+        assert_eq!(unsigned::<usize>("123,4"), Ok((",4", 123)));
+        assert_eq!(unsigned::<i32>("42"), Ok(("", 42)));
+        assert_eq!(signed::<i64>("-17.."), Ok(("..", -17)));
+        assert_eq!(signed::<i8>("+5"), Ok(("", 5)));
+        assert!(unsigned::<u32>("-5").is_err());
+        assert!(signed::<i32>("-").is_err());
+    }
+
+    #[test]
+    fn overflow() {
+        assert_eq!(
+            unsigned::<u8>("256"),
+            Err(nom::Err::Error(Error::new("256", ErrorKind::TooLarge)))
+        );
+        assert_eq!(unsigned::<u8>("255"), Ok(("", 255)));
+        assert_eq!(signed::<i8>("-128"), Ok(("", -128)));
+        assert!(signed::<i8>("-129").is_err());
+        assert!(unsigned::<u64>("99999999999999999999").is_err());
+    }
+
+    #[test]
+    fn lists() {
+        assert_eq!(
+            comma_list(unsigned::<u8>)("3,4,3\n"),
+            Ok(("\n", vec![3, 4, 3]))
+        );
+        assert_eq!(lines_of(alpha1)("ab\ncd\n"), Ok(("\n", vec!["ab", "cd"])));
+        assert_eq!(
+            blank_line_separated(lines_of(unsigned::<u8>))("1\n2\n\n3"),
+            Ok(("", vec![vec![1, 2], vec![3]]))
+        );
+        assert_eq!(comma_list(unsigned::<u8>)("1,300"), Ok((",300", vec![1])));
+    }
+
+    #[test]
+    fn grid() {
+        assert_eq!(
+            digit_grid::<u32>("123\n456\n"),
+            Ok(("\n", vec![vec![1, 2, 3], vec![4, 5, 6]]))
+        );
+        assert!(digit_grid::<u8>("x").is_err());
+    }
+}
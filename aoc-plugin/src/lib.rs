@@ -0,0 +1,163 @@
+//! Stable C-ABI shared by every day crate that is built as a `cdylib` plugin.
+//!
+//! A plugin exports four symbols:
+//!
+//! - `aoc_abi_version() -> u32`, which must equal [`ABI_VERSION`]
+//! - `aoc_day() -> u32`, the puzzle day the plugin solves
+//! - `aoc_solve(part, input_ptr, len) -> *mut c_char`, a NUL-terminated answer
+//!   or a null pointer when the part is unknown, the input is not UTF-8 or the
+//!   solver panicked
+//! - `aoc_free(answer)`, which releases a string returned by `aoc_solve`
+//!
+//! Day crates generate these with [`export_day!`] behind their `plugin` feature.
+
+use std::{
+    ffi::{c_char, CString},
+    panic, slice, str,
+};
+
+/// Bumped whenever the signatures below change.
+pub const ABI_VERSION: u32 = 1;
+
+pub const ABI_VERSION_SYMBOL: &[u8] = b"aoc_abi_version\0";
+pub const DAY_SYMBOL: &[u8] = b"aoc_day\0";
+pub const SOLVE_SYMBOL: &[u8] = b"aoc_solve\0";
+pub const FREE_SYMBOL: &[u8] = b"aoc_free\0";
+
+pub type AbiVersionFn = unsafe extern "C" fn() -> u32;
+pub type DayFn = unsafe extern "C" fn() -> u32;
+pub type SolveFn = unsafe extern "C" fn(u32, *const u8, usize) -> *mut c_char;
+pub type FreeFn = unsafe extern "C" fn(*mut c_char);
+
+/// Runs `part1` or `part2` on the raw input and hands the answer over to C.
+///
+/// # Safety
+///
+/// `input_ptr` must point to `len` readable bytes, or be null when `len` is 0.
+pub unsafe fn solve(
+    part: u32,
+    input_ptr: *const u8,
+    len: usize,
+    part1: fn(&str) -> String,
+    part2: fn(&str) -> String,
+) -> *mut c_char {
+    let bytes = if len == 0 {
+        &[][..]
+    } else if input_ptr.is_null() {
+        return std::ptr::null_mut();
+    } else {
+        slice::from_raw_parts(input_ptr, len)
+    };
+    let Ok(input) = str::from_utf8(bytes) else {
+        return std::ptr::null_mut();
+    };
+    let solver = match part {
+        1 => part1,
+        2 => part2,
+        _ => return std::ptr::null_mut(),
+    };
+    match panic::catch_unwind(|| solver(input)) {
+        Ok(answer) => CString::new(answer)
+            .map(CString::into_raw)
+            .unwrap_or(std::ptr::null_mut()),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Releases an answer previously returned by [`solve`].
+///
+/// # Safety
+///
+/// `answer` must be null or a pointer returned by [`solve`] that hasn't been freed yet.
+pub unsafe fn free(answer: *mut c_char) {
+    if !answer.is_null() {
+        drop(CString::from_raw(answer));
+    }
+}
+
+/// Exports the plugin symbols for a day crate.
+///
+/// `export_day!(18)` uses the crate's `process_part1` and `process_part2`,
+/// other solvers can be passed explicitly: `export_day!(18, fast_part1, fast_part2)`.
+#[macro_export]
+macro_rules! export_day {
+    ($day:expr) => {
+        $crate::export_day!($day, process_part1, process_part2);
+    };
+    ($day:expr, $part1:path, $part2:path) => {
+        #[no_mangle]
+        pub extern "C" fn aoc_abi_version() -> u32 {
+            $crate::ABI_VERSION
+        }
+
+        #[no_mangle]
+        pub extern "C" fn aoc_day() -> u32 {
+            $day
+        }
+
+        /// # Safety
+        ///
+        /// `input_ptr` must point to `len` readable bytes, or be null when `len` is 0.
+        #[no_mangle]
+        pub unsafe extern "C" fn aoc_solve(
+            part: u32,
+            input_ptr: *const u8,
+            len: usize,
+        ) -> *mut ::std::ffi::c_char {
+            $crate::solve(part, input_ptr, len, $part1, $part2)
+        }
+
+        /// # Safety
+        ///
+        /// `answer` must be null or a pointer returned by `aoc_solve` that hasn't been freed yet.
+        #[no_mangle]
+        pub unsafe extern "C" fn aoc_free(answer: *mut ::std::ffi::c_char) {
+            $crate::free(answer)
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+
+    fn process_part1(input: &str) -> String {
+        input.len().to_string()
+    }
+
+    fn process_part2(input: &str) -> String {
+        if input.is_empty() {
+            panic!("Empty input!");
+        }
+        input.to_uppercase()
+    }
+
+    export_day!(7);
+
+    fn call(part: u32, input: &[u8]) -> Option<String> {
+        unsafe {
+            let answer = aoc_solve(part, input.as_ptr(), input.len());
+            if answer.is_null() {
+                return None;
+            }
+            let s = CStr::from_ptr(answer).to_str().unwrap().to_string();
+            aoc_free(answer);
+            Some(s)
+        }
+    }
+
+    #[test]
+    fn exports() {
+        assert_eq!(aoc_abi_version(), super::ABI_VERSION);
+        assert_eq!(aoc_day(), 7);
+        assert_eq!(call(1, b"abc"), Some("3".to_string()));
+        assert_eq!(call(2, b"abc"), Some("ABC".to_string()));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(call(3, b"abc"), None);
+        assert_eq!(call(1, &[0xff, 0xfe]), None);
+        assert_eq!(call(2, b""), None);
+    }
+}
@@ -0,0 +1,371 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    panic::{self, AssertUnwindSafe},
+};
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::days;
+
+fn error<E: Display>(e: E) -> String {
+    e.to_string()
+}
+
+fn remainder(rem: &str) -> Result<(), String> {
+    match rem.trim_start().lines().next() {
+        Some(line) => Err(format!("input after the last parsed item: '{}'", line)),
+        None => Ok(()),
+    }
+}
+
+/// Transforms `input` into a different input for `day` with the same answers
+pub fn anonymize<R: Rng>(day: u32, input: &str, rng: &mut R) -> Result<String, String> {
+    let anonymized = match day {
+        5 => vents(input, rng),
+        8 => displays(input, rng),
+        12 => caves(input, rng),
+        19 => scanners(input, rng),
+        _ => Err(format!(
+            "there is no anonymizer for day {}, try 5, 8, 12 or 19",
+            day
+        )),
+    }?;
+    // some solvers insist on consuming all input, so keep the final newline as it was
+    if input.ends_with('\n') {
+        Ok(anonymized)
+    } else {
+        Ok(anonymized.trim_end().to_string())
+    }
+}
+
+/// Solves both parts for both inputs and returns what was checked, a solver
+/// that panics or has no answer fails the check instead of matching itself
+pub fn verify(day: u32, original: &str, anonymized: &str) -> Result<String, String> {
+    let solver = days::builtin(day).ok_or(format!("day {} isn't implemented", day))?;
+    let solve = |part, input, name| {
+        let answer = panic::catch_unwind(AssertUnwindSafe(|| solver.solve(part, input)))
+            .map_err(|_| format!("part {} panicked on the {} input", part, name))?;
+        answer.ok_or(format!("there is no part {}", part))
+    };
+    for part in [1, 2] {
+        let expected = solve(part, original, "original")?;
+        if expected.is_empty() {
+            // the solver isn't done yet, so compare what it would work from
+            return same_structure(day, original, anonymized);
+        }
+        let answer = solve(part, anonymized, "anonymized")?;
+        if expected != answer {
+            return Err(format!(
+                "part {} changed from {} to {}",
+                part, expected, answer
+            ));
+        }
+    }
+    Ok("both parts give the same answers".to_string())
+}
+
+/// Compares the inputs of a day without answers by what anonymizing has to keep
+fn same_structure(day: u32, original: &str, anonymized: &str) -> Result<String, String> {
+    match day {
+        19 => {
+            let (original, anonymized) = (beacons(original)?, beacons(anonymized)?);
+            if original.len() != anonymized.len() {
+                return Err(format!(
+                    "the number of scanners changed from {} to {}",
+                    original.len(),
+                    anonymized.len()
+                ));
+            }
+            if original != anonymized {
+                return Err("the beacons of a scanner changed".to_string());
+            }
+            Ok("same number of scanners and beacons at the same distances".to_string())
+        }
+        _ => Err(format!("day {} has no answers to verify against", day)),
+    }
+}
+
+/// Number of beacons of every scanner and the squared distances between them,
+/// which no rotation changes
+fn beacons(input: &str) -> Result<Vec<(usize, Vec<i64>)>, String> {
+    let (rem, scanners) = day19::parse_input(input).map_err(error)?;
+    remainder(rem)?;
+    let mut beacons: Vec<(usize, Vec<i64>)> = scanners
+        .iter()
+        .map(|scanner| {
+            let coords = &scanner.coords;
+            let mut distances: Vec<i64> = (0..coords.len())
+                .flat_map(|i| (i + 1..coords.len()).map(move |j| (i, j)))
+                .map(|(i, j)| {
+                    let (a, b) = (coords[i], coords[j]);
+                    [a.x - b.x, a.y - b.y, a.z - b.z]
+                        .iter()
+                        .map(|&d| (d as i64).pow(2))
+                        .sum()
+                })
+                .collect();
+            distances.sort();
+            (coords.len(), distances)
+        })
+        .collect();
+    beacons.sort();
+    Ok(beacons)
+}
+
+/// Mirrors and translates all vents and shuffles their order and direction
+fn vents<R: Rng>(input: &str, rng: &mut R) -> Result<String, String> {
+    let (rem, mut lines) = day05::parse_input(input).map_err(error)?;
+    remainder(rem)?;
+    let points = lines.iter().flat_map(|line| [line.start, line.end]);
+    let max_x = points.clone().map(|p| p.x).max().unwrap_or_default() as i64;
+    let max_y = points.map(|p| p.y).max().unwrap_or_default() as i64;
+    let (flip_x, flip_y) = (rng.gen_bool(0.5), rng.gen_bool(0.5));
+    let (dx, dy) = (rng.gen_range(0..=100), rng.gen_range(0..=100));
+    let transform = |value: usize, flip: bool, max: i64, offset: i64| {
+        let value = value as i64;
+        (if flip { max - value } else { value } + offset) as usize
+    };
+
+    lines.shuffle(rng);
+    let mut output = String::new();
+    for line in lines {
+        let (mut start, mut end) = (line.start, line.end);
+        if rng.gen_bool(0.5) {
+            (start, end) = (end, start);
+        }
+        let [x1, x2] = [start.x, end.x].map(|x| transform(x, flip_x, max_x, dx));
+        let [y1, y2] = [start.y, end.y].map(|y| transform(y, flip_y, max_y, dy));
+        output.push_str(&format!("{},{} -> {},{}\n", x1, y1, x2, y2));
+    }
+    Ok(output)
+}
+
+/// Rewires every display with its own segment permutation and shuffles the patterns
+fn displays<R: Rng>(input: &str, rng: &mut R) -> Result<String, String> {
+    let (rem, displays) = day08::parse_input(input).map_err(error)?;
+    remainder(rem)?;
+    let mut output = String::new();
+    for display in displays {
+        let mut segments: Vec<char> = ('a'..='g').collect();
+        segments.shuffle(rng);
+        let mut rewire = |pattern: &String| {
+            let mut wires: Vec<char> = pattern
+                .chars()
+                .map(|c| segments[c as usize - 'a' as usize])
+                .collect();
+            wires.shuffle(rng);
+            wires.into_iter().collect::<String>()
+        };
+        let mut patterns: Vec<String> = display.patterns.iter().map(&mut rewire).collect();
+        let outputs: Vec<String> = display.output.iter().map(&mut rewire).collect();
+        patterns.shuffle(rng);
+        output.push_str(&format!("{} | {}\n", patterns.join(" "), outputs.join(" ")));
+    }
+    Ok(output)
+}
+
+/// Random name with the same case rules as `cave`: all uppercase, all lowercase or mixed
+fn cave_name<R: Rng>(cave: &str, len: usize, rng: &mut R) -> String {
+    let upper = cave.chars().all(|c| c.is_uppercase());
+    let mixed = !upper && cave.chars().any(|c| c.is_uppercase());
+    (0..len)
+        .map(|i| {
+            let c = rng.gen_range('a'..='z');
+            if upper || (mixed && i == 0) {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// Renames every cave except start and end and shuffles the tunnels
+fn caves<R: Rng>(input: &str, rng: &mut R) -> Result<String, String> {
+    let (rem, graph) = day12::parse_input(input).map_err(error)?;
+    remainder(rem)?;
+    let mut caves: Vec<&str> = graph.caves().collect();
+    caves.sort();
+    let mut names: HashMap<&str, String> = HashMap::new();
+    let mut taken: HashSet<String> = ["start", "end"].map(String::from).into();
+    for cave in caves {
+        if cave == "start" || cave == "end" {
+            names.insert(cave, cave.to_string());
+            continue;
+        }
+        let mut attempts = 0;
+        let name = loop {
+            let name = cave_name(cave, 2 + attempts / 100, rng);
+            if taken.insert(name.clone()) {
+                break name;
+            }
+            attempts += 1;
+        };
+        names.insert(cave, name);
+    }
+
+    let mut tunnels: Vec<(&str, &str)> = input
+        .lines()
+        .filter_map(|line| line.trim().split_once('-'))
+        .collect();
+    tunnels.shuffle(rng);
+    let mut output = String::new();
+    for (mut from, mut to) in tunnels {
+        if rng.gen_bool(0.5) {
+            (from, to) = (to, from);
+        }
+        output.push_str(&format!("{}-{}\n", names[from], names[to]));
+    }
+    Ok(output)
+}
+
+/// All 24 rotations as (axis permutation, signs)
+fn rotations() -> Vec<([usize; 3], [i32; 3])> {
+    let permutations = [
+        ([0, 1, 2], 1),
+        ([1, 2, 0], 1),
+        ([2, 0, 1], 1),
+        ([0, 2, 1], -1),
+        ([2, 1, 0], -1),
+        ([1, 0, 2], -1),
+    ];
+    let mut rotations = Vec::new();
+    for (axes, parity) in permutations {
+        for signs in 0..8 {
+            let signs = [0, 1, 2].map(|bit| if signs & (1 << bit) == 0 { 1 } else { -1 });
+            if parity * signs.iter().product::<i32>() == 1 {
+                rotations.push((axes, signs));
+            }
+        }
+    }
+    rotations
+}
+
+/// Turns every scanner by a random rotation and shuffles scanners and beacons
+fn scanners<R: Rng>(input: &str, rng: &mut R) -> Result<String, String> {
+    let (rem, mut scanners) = day19::parse_input(input).map_err(error)?;
+    remainder(rem)?;
+    let rotations = rotations();
+    scanners.shuffle(rng);
+    let mut reports = Vec::new();
+    for (id, scanner) in scanners.iter_mut().enumerate() {
+        let (axes, signs) = rotations.choose(rng).unwrap();
+        scanner.coords.shuffle(rng);
+        let mut report = format!("--- scanner {} ---\n", id);
+        for coord in scanner.coords.iter() {
+            let xyz = [coord.x, coord.y, coord.z];
+            let [x, y, z] = [0, 1, 2].map(|i| xyz[axes[i]] * signs[i]);
+            report.push_str(&format!("{},{},{}\n", x, y, z));
+        }
+        reports.push(report);
+    }
+    Ok(reports.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn check(day: u32, input: &str) -> String {
+        let mut rng = StdRng::seed_from_u64(2021);
+        let anonymized = anonymize(day, input, &mut rng).unwrap();
+        assert_ne!(anonymized.trim(), input.trim());
+        verify(day, input, &anonymized).unwrap();
+        anonymized
+    }
+
+    #[test]
+    fn vents() {
+        check(
+            5,
+            "0,9 -> 5,9
+8,0 -> 0,8
+9,4 -> 3,4
+2,2 -> 2,1
+7,0 -> 7,4
+6,4 -> 2,0
+0,9 -> 2,9
+3,4 -> 1,4
+0,0 -> 8,8
+5,5 -> 8,2",
+        );
+    }
+
+    #[test]
+    fn displays() {
+        let anonymized = check(
+            8,
+            "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe
+edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc
+fgaebd cg bdaec gdafb agbcfd gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg",
+        );
+        assert_eq!(anonymized.lines().count(), 3);
+    }
+
+    #[test]
+    fn caves() {
+        let input =
+            "dc-end\nHN-start\nstart-kj\ndc-start\ndc-HN\nLN-dc\nHN-end\nkj-sa\nkj-HN\nkj-dc";
+        let anonymized = check(12, input);
+        assert!(!anonymized.contains("HN") && !anonymized.contains("kj"));
+        assert_eq!(anonymized.matches("start").count(), 3);
+        assert_eq!(anonymized.matches("end").count(), 2);
+    }
+
+    #[test]
+    fn rotations() {
+        let rotations = super::rotations();
+        assert_eq!(rotations.len(), 24);
+        let turned: HashSet<[i32; 3]> = rotations
+            .iter()
+            .map(|(axes, signs)| [0, 1, 2].map(|i| [1, 2, 3][axes[i]] * signs[i]))
+            .collect();
+        assert_eq!(turned.len(), 24);
+        assert!(turned.contains(&[2, -1, 3]));
+        assert!(!turned.contains(&[-1, 2, 3]));
+    }
+
+    #[test]
+    fn scanners() {
+        let input = "--- scanner 0 ---\n404,-588,-901\n528,-643,409\n\n--- scanner 1 ---\n686,422,578\n605,423,415\n515,917,-361";
+        let mut rng = StdRng::seed_from_u64(19);
+        let anonymized = anonymize(19, input, &mut rng).unwrap();
+        let (_, scanners) = day19::parse_input(&anonymized).unwrap();
+        let mut lengths: Vec<usize> = scanners.iter().map(|s| s.coords.len()).collect();
+        lengths.sort();
+        assert_eq!(lengths, vec![2, 3]);
+        assert_eq!(scanners[0].id, 0);
+        let distance = |scanner: &day19::Scanner| {
+            let (a, b) = (scanner.coords[0], scanner.coords[1]);
+            (a.x - b.x).pow(2) + (a.y - b.y).pow(2) + (a.z - b.z).pow(2)
+        };
+        let short = scanners.iter().find(|s| s.coords.len() == 2).unwrap();
+        assert_eq!(distance(short), 124 * 124 + 55 * 55 + 1310 * 1310);
+        assert!(verify(19, input, &anonymized).is_ok());
+        let moved = input.replace("515,917,-361", "515,917,-360");
+        assert!(verify(19, input, &moved).is_err());
+        assert!(verify(19, input, input.split("\n\n").next().unwrap()).is_err());
+    }
+
+    #[test]
+    fn unsupported() {
+        let mut rng = StdRng::seed_from_u64(1);
+        assert!(anonymize(1, "1", &mut rng).is_err());
+        assert!(anonymize(12, "start-A\n???", &mut rng).is_err());
+    }
+
+    #[test]
+    fn differs() {
+        assert_eq!(
+            verify(6, "3,4,3,1,2", "3,4,3,1,1"),
+            Err("part 1 changed from 5934 to 6144".to_string())
+        );
+        assert_eq!(
+            verify(16, "not hex", "also not hex"),
+            Err("part 1 panicked on the original input".to_string())
+        );
+    }
+}
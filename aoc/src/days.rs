@@ -0,0 +1,77 @@
+pub type SolveFn = fn(&str) -> String;
+
+/// A built-in day, compiled into the runner.
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub day: u32,
+    pub part1: SolveFn,
+    pub part2: SolveFn,
+}
+
+impl Day {
+    pub fn solve(&self, part: u32, input: &str) -> Option<String> {
+        match part {
+            1 => Some((self.part1)(input)),
+            2 => Some((self.part2)(input)),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! days {
+    ($($day:literal => $krate:ident),* $(,)?) => {
+        pub const DAYS: &[Day] = &[
+            $(Day {
+                day: $day,
+                part1: $krate::process_part1,
+                part2: $krate::process_part2,
+            }),*
+        ];
+    };
+}
+
+days! {
+    1 => day01,
+    2 => day02,
+    3 => day03,
+    4 => day04,
+    5 => day05,
+    6 => day06,
+    7 => day07,
+    8 => day08,
+    9 => day09,
+    10 => day10,
+    11 => day11,
+    12 => day12,
+    13 => day13,
+    14 => day14,
+    15 => day15,
+    16 => day16,
+    17 => day17,
+    18 => day18,
+    19 => day19,
+    20 => day20,
+    21 => day21,
+    22 => day22,
+    23 => day23,
+    24 => day24,
+    25 => day25,
+}
+
+pub fn builtin(day: u32) -> Option<Day> {
+    DAYS.iter().find(|d| d.day == day).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        assert!(builtin(0).is_none());
+        assert!(builtin(26).is_none());
+        let day = builtin(6).unwrap();
+        assert_eq!(day.solve(1, "3,4,3,1,2"), Some("5934".to_string()));
+        assert_eq!(day.solve(3, "3,4,3,1,2"), None);
+    }
+}
@@ -0,0 +1,133 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Json,
+    Yaml,
+}
+
+/// Day 14's insertion rules keyed by pair, since JSON keys have to be strings
+#[derive(Serialize)]
+struct Polymer {
+    template: String,
+    rules: BTreeMap<String, char>,
+}
+
+fn render<T: Serialize>(value: &T, format: Format) -> Result<String, String> {
+    match format {
+        Format::Json => serde_json::to_string_pretty(value)
+            .map(|json| json + "\n")
+            .map_err(|e| e.to_string()),
+        Format::Yaml => serde_yaml::to_string(value).map_err(|e| e.to_string()),
+    }
+}
+
+/// Renders the result of a day's `parse_input`, along with the input it didn't consume
+fn parsed<T: Serialize, E: Display>(
+    result: Result<(&str, T), E>,
+    format: Format,
+) -> Result<(String, &str), String> {
+    let (rem, value) = result.map_err(|e| e.to_string())?;
+    Ok((render(&value, format)?, rem))
+}
+
+/// Renders the parsed input of `day`, returns it with the unparsed remainder of the input
+pub fn dump(day: u32, input: &str, format: Format) -> Result<(String, &str), String> {
+    match day {
+        1 => parsed(day01::parse_input(input), format),
+        2 => parsed(day02::parse_input(input), format),
+        3 => {
+            let (rem, report) = day03::parse_input(input).map_err(|e| e.to_string())?;
+            Ok((render(&report.lines(), format)?, rem))
+        }
+        4 => parsed(day04::parse_input(input), format),
+        5 => parsed(day05::parse_input(input), format),
+        6 => parsed(day06::parse_input(input), format),
+        7 => parsed(day07::parse_input(input), format),
+        8 => parsed(day08::parse_input(input), format),
+        9 => parsed(day09::parse_input(input), format),
+        10 => parsed(day10::parse_input(input), format),
+        11 => parsed(day11::parse_input(input), format),
+        12 => parsed(day12::parse_input(input), format),
+        13 => parsed(day13::parse_input(input), format),
+        14 => {
+            let (rem, (template, rules)) = day14::parse_input(input).map_err(|e| e.to_string())?;
+            let polymer = Polymer {
+                template: template.into_iter().collect(),
+                rules: rules
+                    .into_iter()
+                    .map(|((left, right), c)| (format!("{}{}", left, right), c))
+                    .collect(),
+            };
+            Ok((render(&polymer, format)?, rem))
+        }
+        15 => parsed(day15::parse_input(input), format),
+        16 => {
+            let (rem, bits) = day16::parse_input(input).map_err(|e| e.to_string())?;
+            let (_, packet) = day16::parse_input_bits(&bits).map_err(|e| e.to_string())?;
+            Ok((render(&packet, format)?, rem))
+        }
+        17 => parsed(day17::parse_input(input), format),
+        18 => {
+            let (rem, nums) = day18::parse_input(input).map_err(|e| e.to_string())?;
+            let nums: Vec<_> = nums.iter().map(|num| num.borrow().clone()).collect();
+            Ok((render(&nums, format)?, rem))
+        }
+        19 => parsed(day19::parse_input(input), format),
+        _ => Err(format!("day {} isn't implemented", day)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json() {
+        let (json, rem) = dump(2, "forward 5\ndown 5\nup 3\n", Format::Json).unwrap();
+        assert_eq!(
+            json,
+            "[\n  {\n    \"Forward\": 5\n  },\n  {\n    \"Down\": 5\n  },\n  {\n    \"Up\": 3\n  }\n]\n"
+        );
+        assert_eq!(rem, "\n");
+    }
+
+    #[test]
+    fn yaml() {
+        let (yaml, _) = dump(6, "3,4,3", Format::Yaml).unwrap();
+        assert_eq!(yaml, "- timer: 3\n- timer: 4\n- timer: 3\n");
+        let (yaml, _) = dump(14, "NNC\n\nNN -> C\nCN -> B", Format::Yaml).unwrap();
+        assert_eq!(yaml, "template: NNC\nrules:\n  CN: 'B'\n  NN: 'C'\n");
+    }
+
+    #[test]
+    fn packet() {
+        let (json, _) = dump(16, "D2FE28", Format::Json).unwrap();
+        let packet: day16::Packet = serde_json::from_str(&json).unwrap();
+        assert_eq!(packet, day16::Packet::Literal(6, 2021));
+    }
+
+    #[test]
+    fn snailfish() {
+        let (json, _) = dump(18, "[[[[[9,8],1],2],3],4]", Format::Json).unwrap();
+        let nums: Vec<day18::Num> = serde_json::from_str(&json).unwrap();
+        let num = std::rc::Rc::new(std::cell::RefCell::new(nums[0].clone()));
+        assert_eq!(num.borrow().to_string(), "[[[[[9,8],1],2],3],4]");
+        day18::connect(num.clone());
+        let num = day18::reduce(num);
+        assert_eq!(num.borrow().to_string(), "[[[[0,9],2],3],4]");
+        assert!(serde_json::from_str::<day18::Num>("[1,2,3]").is_err());
+        assert!(serde_json::from_str::<day18::Num>("256").is_err());
+    }
+
+    #[test]
+    fn remainder() {
+        let (_, rem) = dump(1, "1\n2\nthree\n", Format::Json).unwrap();
+        assert_eq!(rem, "\nthree\n");
+        assert!(dump(16, "XYZ", Format::Json).is_err());
+        assert!(dump(26, "", Format::Json).is_err());
+    }
+}
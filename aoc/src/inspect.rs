@@ -0,0 +1,374 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+/// Statistics about a puzzle input and anomalies the solvers may trip over
+#[derive(Debug, Default)]
+pub struct Report {
+    pub stats: Vec<(String, String)>,
+    pub warnings: Vec<String>,
+}
+
+impl Report {
+    fn stat<V: Display>(&mut self, name: &str, value: V) {
+        self.stats.push((name.to_string(), value.to_string()));
+    }
+
+    fn warn<S: Into<String>>(&mut self, warning: S) {
+        self.warnings.push(warning.into());
+    }
+
+    /// Warns about input the parser didn't consume
+    fn remainder(&mut self, rem: &str) {
+        if !rem.trim().is_empty() {
+            let line = rem.trim_start().lines().next().unwrap_or_default();
+            self.warn(format!("input after the last parsed item: '{}'", line));
+        }
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self.stats.iter().map(|(name, _)| name.len()).max();
+        for (name, value) in self.stats.iter() {
+            writeln!(f, "{:<w$}  {}", name, value, w = width.unwrap_or(0))?;
+        }
+        for warning in self.warnings.iter() {
+            writeln!(f, "warning: {}", warning)?;
+        }
+        Ok(())
+    }
+}
+
+fn error<E: Display>(e: E) -> String {
+    e.to_string()
+}
+
+/// Builds the report for any day, with extra statistics for days 4, 5, 9, 11, 12, 15 and 16
+pub fn inspect(day: u32, input: &str) -> Result<Report, String> {
+    let mut report = Report::default();
+    report.stat("bytes", input.len());
+    report.stat("lines", input.lines().count());
+    if input.lines().any(|line| line.ends_with('\r')) {
+        report.warn("lines end in CRLF, the parsers expect LF");
+    }
+    match day {
+        4 => {
+            let (rem, (draws, boards)) = day04::parse_input(input).map_err(error)?;
+            report.remainder(rem);
+            bingo(&mut report, &draws, &boards);
+        }
+        5 => {
+            let (rem, lines) = day05::parse_input(input).map_err(error)?;
+            report.remainder(rem);
+            vents(&mut report, &lines);
+        }
+        9 | 11 | 15 => {
+            let rows: Vec<String> = match day {
+                9 => {
+                    let (rem, rows) = day09::parse_input(input).map_err(error)?;
+                    report.remainder(rem);
+                    rows.into_iter().map(str::to_string).collect()
+                }
+                11 => {
+                    let (rem, octopuses) = day11::parse_input(input).map_err(error)?;
+                    report.remainder(rem);
+                    octopuses.to_string().lines().map(str::to_string).collect()
+                }
+                _ => {
+                    let (rem, rows) = day15::parse_input(input).map_err(error)?;
+                    report.remainder(rem);
+                    rows.into_iter()
+                        .map(|row| row.iter().map(|risk| risk.to_string()).collect())
+                        .collect()
+                }
+            };
+            grid(&mut report, &rows);
+        }
+        12 => {
+            let (rem, graph) = day12::parse_input(input).map_err(error)?;
+            report.remainder(rem);
+            caves(&mut report, &graph);
+        }
+        16 => {
+            let (rem, bits) = day16::parse_input(input).map_err(error)?;
+            report.remainder(rem);
+            let (padding, packet) = day16::parse_input_bits(&bits).map_err(error)?;
+            transmission(&mut report, &bits, padding, &packet);
+        }
+        _ => {}
+    }
+    Ok(report)
+}
+
+fn bingo(report: &mut Report, draws: &[usize], boards: &[Vec<Vec<usize>>]) {
+    report.stat("draws", draws.len());
+    report.stat("boards", boards.len());
+
+    let mut seen = HashSet::new();
+    let duplicates: Vec<_> = draws.iter().filter(|&d| !seen.insert(d)).collect();
+    if !duplicates.is_empty() {
+        report.warn(format!("numbers drawn more than once: {:?}", duplicates));
+    }
+    for (i, board) in boards.iter().enumerate() {
+        if board.len() != 5 || board.iter().any(|row| row.len() != 5) {
+            report.warn(format!("board {} isn't 5x5", i + 1));
+        }
+        let mut seen = HashSet::new();
+        let duplicates: Vec<_> = board
+            .iter()
+            .flatten()
+            .filter(|&n| !seen.insert(n))
+            .collect();
+        if !duplicates.is_empty() {
+            report.warn(format!(
+                "board {} has duplicate numbers: {:?}",
+                i + 1,
+                duplicates
+            ));
+        }
+    }
+    // the solver marks drawn numbers by replacing them with 100
+    if draws
+        .iter()
+        .chain(boards.iter().flatten().flatten())
+        .any(|&n| n == 100)
+    {
+        report.warn("the number 100 is used as the marker for drawn numbers");
+    }
+}
+
+fn vents(report: &mut Report, lines: &[day05::Line]) {
+    report.stat("lines of vents", lines.len());
+    let points = lines.iter().flat_map(|line| [line.start, line.end]);
+    let (xs, ys): (Vec<_>, Vec<_>) = points.map(|p| (p.x, p.y)).unzip();
+    if let (Some(x_min), Some(x_max), Some(y_min), Some(y_max)) = (
+        xs.iter().min(),
+        xs.iter().max(),
+        ys.iter().min(),
+        ys.iter().max(),
+    ) {
+        report.stat("x range", format!("{}..={}", x_min, x_max));
+        report.stat("y range", format!("{}..={}", y_min, y_max));
+    }
+
+    let (mut horizontal, mut vertical, mut diagonal) = (0, 0, 0);
+    for (i, line) in lines.iter().enumerate() {
+        let dx = line.start.x.abs_diff(line.end.x);
+        let dy = line.start.y.abs_diff(line.end.y);
+        match (dx, dy) {
+            (0, 0) => report.warn(format!("line {} is a single point", i + 1)),
+            (_, 0) => horizontal += 1,
+            (0, _) => vertical += 1,
+            _ if dx == dy => diagonal += 1,
+            _ => report.warn(format!(
+                "line {} isn't horizontal, vertical or diagonal at 45 degrees",
+                i + 1
+            )),
+        }
+    }
+    report.stat("horizontal", horizontal);
+    report.stat("vertical", vertical);
+    report.stat("diagonal", diagonal);
+}
+
+fn grid(report: &mut Report, rows: &[String]) {
+    let height = rows.len();
+    let width = rows.first().map_or(0, |row| row.len());
+    report.stat("grid", format!("{}x{}", width, height));
+    if let Some(i) = rows.iter().position(|row| row.len() != width) {
+        report.warn(format!(
+            "row {} has {} columns instead of {}",
+            i + 1,
+            rows[i].len(),
+            width
+        ));
+    }
+    if width != height {
+        report.warn(format!("the grid isn't square ({}x{})", width, height));
+    }
+}
+
+fn caves(report: &mut Report, graph: &day12::Graph) {
+    let is_big = |cave: &str| cave.chars().all(|c| c.is_uppercase());
+    let caves: Vec<_> = graph.caves().collect();
+    let big = caves.iter().filter(|cave| is_big(cave)).count();
+    let edges: usize = caves
+        .iter()
+        .map(|cave| graph.neighbours(cave).map_or(0, |n| n.len()))
+        .sum();
+    report.stat("caves", caves.len());
+    report.stat("big caves", big);
+    report.stat("small caves", caves.len() - big);
+    report.stat("tunnels", edges / 2);
+
+    for cave in ["start", "end"] {
+        if graph.cave(cave).is_none() {
+            report.warn(format!("there is no '{}' cave", cave));
+        }
+    }
+    let mut adjacent: Vec<_> = caves
+        .iter()
+        .filter(|cave| is_big(cave))
+        .flat_map(|&cave| {
+            graph
+                .neighbours(cave)
+                .unwrap_or_default()
+                .iter()
+                .filter(move |&&other| is_big(other) && cave < other)
+                .map(move |&other| format!("{}-{}", cave, other))
+        })
+        .collect();
+    adjacent.sort();
+    if !adjacent.is_empty() {
+        report.warn(format!(
+            "adjacent big caves allow infinitely many paths: {}",
+            adjacent.join(", ")
+        ));
+    }
+    let mut mixed_case: Vec<_> = caves
+        .iter()
+        .filter(|cave| !is_big(cave) && cave.chars().any(|c| c.is_uppercase()))
+        .collect();
+    mixed_case.sort();
+    if !mixed_case.is_empty() {
+        report.warn(format!(
+            "caves with mixed case names count as small: {:?}",
+            mixed_case
+        ));
+    }
+}
+
+fn transmission(report: &mut Report, bits: &str, padding: &str, packet: &day16::Packet) {
+    fn walk(
+        packet: &day16::Packet,
+        depth: usize,
+        counts: &mut HashMap<&'static str, usize>,
+    ) -> usize {
+        match packet {
+            day16::Packet::Literal(_, _) => {
+                *counts.entry("literal").or_default() += 1;
+                depth
+            }
+            day16::Packet::Operator(_, id, packets) => {
+                *counts.entry(day16::Packet::operation(*id)).or_default() += 1;
+                packets
+                    .iter()
+                    .map(|p| walk(p, depth + 1, counts))
+                    .max()
+                    .unwrap_or(depth)
+            }
+        }
+    }
+
+    let mut counts = HashMap::new();
+    let depth = walk(packet, 1, &mut counts);
+    report.stat("bits", bits.len());
+    report.stat("packets", counts.values().sum::<usize>());
+    report.stat("nesting depth", depth);
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort();
+    for (operation, count) in counts {
+        report.stat(&format!("  {}", operation), count);
+    }
+    report.stat("padding bits", padding.len());
+    if padding.contains('1') {
+        report.warn("the padding after the outermost packet isn't all zeroes");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stat<'a>(report: &'a Report, name: &str) -> &'a str {
+        &report.stats.iter().find(|(n, _)| n == name).unwrap().1
+    }
+
+    #[test]
+    fn generic() {
+        let report = inspect(7, "16,1,2,0,4,2,7,1,2,14\n").unwrap();
+        assert_eq!(report.to_string(), "bytes  22\nlines  1\n");
+    }
+
+    #[test]
+    fn bingo() {
+        let input = "7,4,9,4
+
+22 13 17 11  0
+8  2 23  4 24
+21  9 14 16  7
+6 10  3 18  5
+1 12 20 15 19
+
+3 15  0  2 22
+9 18 13 17  5
+19  8  7 25 23
+20 11 10 24  4
+14 21 16 12 12";
+        let report = inspect(4, input).unwrap();
+        assert_eq!(stat(&report, "draws"), "4");
+        assert_eq!(stat(&report, "boards"), "2");
+        assert_eq!(
+            report.warnings,
+            vec![
+                "numbers drawn more than once: [4]",
+                "board 2 has duplicate numbers: [12]"
+            ]
+        );
+    }
+
+    #[test]
+    fn vents() {
+        let input = "0,9 -> 5,9
+8,0 -> 0,8
+7,0 -> 7,4
+1,1 -> 3,2";
+        let report = inspect(5, input).unwrap();
+        assert_eq!(stat(&report, "x range"), "0..=8");
+        assert_eq!(stat(&report, "y range"), "0..=9");
+        assert_eq!(stat(&report, "diagonal"), "1");
+        assert_eq!(
+            report.warnings,
+            vec!["line 4 isn't horizontal, vertical or diagonal at 45 degrees"]
+        );
+    }
+
+    #[test]
+    fn grid() {
+        let report = inspect(9, "2199943210\n3987894921\n9856789892").unwrap();
+        assert_eq!(stat(&report, "grid"), "10x3");
+        assert_eq!(report.warnings, vec!["the grid isn't square (10x3)"]);
+        let report = inspect(15, "116\n138\n213\n").unwrap();
+        assert_eq!(stat(&report, "grid"), "3x3");
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn caves() {
+        let report = inspect(12, "start-A\nA-b\nA-B\nb-end").unwrap();
+        assert_eq!(stat(&report, "big caves"), "2");
+        assert_eq!(stat(&report, "small caves"), "3");
+        assert_eq!(stat(&report, "tunnels"), "4");
+        assert_eq!(
+            report.warnings,
+            vec!["adjacent big caves allow infinitely many paths: A-B"]
+        );
+    }
+
+    #[test]
+    fn transmission() {
+        let report = inspect(16, "38006F45291200").unwrap();
+        assert_eq!(stat(&report, "bits"), "56");
+        assert_eq!(stat(&report, "packets"), "3");
+        assert_eq!(stat(&report, "  literal"), "2");
+        assert_eq!(stat(&report, "padding bits"), "7");
+        assert!(report.warnings.is_empty());
+        let report = inspect(16, "D2FE29").unwrap();
+        assert_eq!(
+            report.warnings,
+            vec!["the padding after the outermost packet isn't all zeroes"]
+        );
+    }
+}
@@ -0,0 +1,318 @@
+//! Runner for all days.
+//!
+//! Solvers can also be loaded as plugins: build a day crate as a `cdylib` with
+//! `cargo rustc --release --lib --features plugin --crate-type cdylib`, copy
+//! its `libanswer.so` into the plugins directory under a descriptive name
+//! (e.g. `day18-experimental.so`) and run `aoc bench 18` to compare it against
+//! the built-in solver.
+//!
+//! Built with `--features trace`, `--trace FILE` records the spans and events
+//! the solvers emit at their key steps, e.g. every packet of day 16.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::{Duration, Instant},
+};
+
+use clap::{Parser, Subcommand};
+use rand::{rngs::StdRng, SeedableRng};
+
+mod anonymize;
+mod days;
+mod dump;
+mod inspect;
+mod plugin;
+mod repl;
+mod tui;
+
+#[derive(Parser)]
+#[command(about = "Advent of Code 2021 runner")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Write the solvers' trace events to this file (needs the `trace` feature)
+    #[arg(long, global = true, value_name = "FILE")]
+    trace: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve a day with the built-in solver
+    Run {
+        day: u32,
+        /// Only solve this part
+        #[arg(short, long)]
+        part: Option<u32>,
+        /// Puzzle input, defaults to the day's input.txt
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+    },
+    /// Time the built-in solver against plugins for the same day
+    Bench {
+        day: u32,
+        /// Only benchmark this part
+        #[arg(short, long)]
+        part: Option<u32>,
+        /// Puzzle input, defaults to the day's input.txt
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+        /// Directory with plugin libraries
+        #[arg(long, default_value = "plugins")]
+        plugins: PathBuf,
+        #[arg(long, default_value_t = 10)]
+        iterations: u32,
+    },
+    /// Describe an input and warn about anomalies before solving it
+    Inspect {
+        day: u32,
+        /// Puzzle input, defaults to the day's input.txt
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+    },
+    /// Rewrite an input of day 5, 8, 12 or 19 into a different one with the same answers
+    Anonymize {
+        day: u32,
+        /// Puzzle input, defaults to the day's input.txt
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+        /// Write the anonymized input here instead of to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Seed for a reproducible result
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// Print the parsed input, as the day's parser interpreted it
+    Dump {
+        day: u32,
+        /// Puzzle input, defaults to the day's input.txt
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+        #[arg(short, long, value_enum, default_value_t = dump::Format::Json)]
+        format: dump::Format,
+    },
+    /// Explore the parsed puzzle state of day 11, 12, 13, 16 or 18 interactively
+    Repl {
+        day: u32,
+        /// Puzzle input, defaults to the day's input.txt
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+    },
+    /// Report how the depth changes over the sonar sweep of day 1
+    Trend {
+        /// Readings per moving average
+        #[arg(short, long, default_value_t = 3)]
+        window: usize,
+        /// Puzzle input, defaults to the day's input.txt
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+    },
+    /// Watch the simulation of day 4, 6, 11 or 17 step by step
+    Tui {
+        day: u32,
+        /// Puzzle input, defaults to the day's input.txt
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Some(path) = cli.trace {
+        if let Err(e) = trace(&path) {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    }
+    let result = match cli.command {
+        Command::Run { day, part, input } => run(day, part, input),
+        Command::Bench {
+            day,
+            part,
+            input,
+            plugins,
+            iterations,
+        } => bench(day, part, input, &plugins, iterations),
+        Command::Inspect { day, input } => read_input(day, input)
+            .and_then(|input| inspect::inspect(day, &input))
+            .map(|report| print!("{}", report)),
+        Command::Anonymize {
+            day,
+            input,
+            output,
+            seed,
+        } => anonymize(day, input, output, seed),
+        Command::Dump { day, input, format } => dump(day, input, format),
+        Command::Repl { day, input } => read_input(day, input)
+            .and_then(|input| repl::run(day, &input, std::io::stdin().lock(), std::io::stdout())),
+        Command::Trend { window, input } => trend(window, input),
+        Command::Tui { day, input } => {
+            read_input(day, input).and_then(|input| tui::run(day, &input))
+        }
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(feature = "trace")]
+fn trace(path: &Path) -> Result<(), String> {
+    let file =
+        fs::File::create(path).map_err(|e| format!("can't create {}: {}", path.display(), e))?;
+    tracing_subscriber::fmt()
+        .with_writer(std::sync::Mutex::new(file))
+        .with_ansi(false)
+        .with_max_level(tracing_subscriber::filter::LevelFilter::TRACE)
+        .init();
+    Ok(())
+}
+
+#[cfg(not(feature = "trace"))]
+fn trace(_path: &Path) -> Result<(), String> {
+    Err("tracing isn't compiled in, rebuild with `--features trace`".to_string())
+}
+
+fn default_input(day: u32) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join(format!("day-{:02}", day))
+        .join("input.txt")
+}
+
+fn read_input(day: u32, input: Option<PathBuf>) -> Result<String, String> {
+    let path = input.unwrap_or_else(|| default_input(day));
+    fs::read_to_string(&path).map_err(|e| format!("can't read {}: {}", path.display(), e))
+}
+
+fn parts(part: Option<u32>) -> Result<Vec<u32>, String> {
+    match part {
+        None => Ok(vec![1, 2]),
+        Some(p @ (1 | 2)) => Ok(vec![p]),
+        Some(p) => Err(format!("there is no part {}", p)),
+    }
+}
+
+fn trend(window: usize, input: Option<PathBuf>) -> Result<(), String> {
+    let input = read_input(1, input)?;
+    let (rem, depths) = day01::parse_input(&input).map_err(|e| e.to_string())?;
+    if let Some(line) = rem.trim_start().lines().next() {
+        return Err(format!("input after the last parsed item: '{}'", line));
+    }
+    print!("{}", day01::trend(depths, window));
+    Ok(())
+}
+
+fn run(day: u32, part: Option<u32>, input: Option<PathBuf>) -> Result<(), String> {
+    let solver = days::builtin(day).ok_or(format!("day {} isn't implemented", day))?;
+    let parts = parts(part)?;
+    let input = read_input(day, input)?;
+    for part in parts {
+        println!("Part {}: {}", part, solver.solve(part, &input).unwrap());
+    }
+    Ok(())
+}
+
+fn anonymize(
+    day: u32,
+    input: Option<PathBuf>,
+    output: Option<PathBuf>,
+    seed: Option<u64>,
+) -> Result<(), String> {
+    let input = read_input(day, input)?;
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let anonymized = anonymize::anonymize(day, &input, &mut rng)?;
+    let verified = anonymize::verify(day, &input, &anonymized)?;
+    eprintln!("verified: {}", verified);
+    match output {
+        Some(path) => fs::write(&path, anonymized)
+            .map_err(|e| format!("can't write {}: {}", path.display(), e)),
+        None => {
+            print!("{}", anonymized);
+            Ok(())
+        }
+    }
+}
+
+fn dump(day: u32, input: Option<PathBuf>, format: dump::Format) -> Result<(), String> {
+    let input = read_input(day, input)?;
+    let (output, rem) = dump::dump(day, &input, format)?;
+    print!("{}", output);
+    if !rem.trim().is_empty() {
+        let line = rem.trim_start().lines().next().unwrap_or_default();
+        eprintln!("warning: input after the last parsed item: '{}'", line);
+    }
+    Ok(())
+}
+
+fn bench(
+    day: u32,
+    part: Option<u32>,
+    input: Option<PathBuf>,
+    plugin_dir: &Path,
+    iterations: u32,
+) -> Result<(), String> {
+    let parts = parts(part)?;
+    let input = read_input(day, input)?;
+    let builtin = days::builtin(day);
+    let mut plugins = Vec::new();
+    if plugin_dir.is_dir() {
+        for plugin in plugin::load_dir(plugin_dir).map_err(|e| e.to_string())? {
+            match plugin {
+                Ok(plugin) if plugin.day == day => plugins.push(plugin),
+                Ok(_) => {}
+                Err((path, e)) => eprintln!("skipping {}: {}", path.display(), e),
+            }
+        }
+    }
+    if builtin.is_none() && plugins.is_empty() {
+        return Err(format!("no solver for day {}", day));
+    }
+
+    let iterations = iterations.max(1);
+    for part in parts {
+        let expected = builtin.map(|d| d.solve(part, &input).unwrap());
+        if let Some(d) = builtin {
+            let (answer, time) = time(iterations, || d.solve(part, &input));
+            report(part, "built-in", answer, time, expected.as_deref());
+        }
+        for plugin in plugins.iter() {
+            let (answer, time) = time(iterations, || plugin.solve(part, &input));
+            report(part, &plugin.name(), answer, time, expected.as_deref());
+        }
+    }
+    Ok(())
+}
+
+/// Returns the last answer and the mean duration
+fn time<F>(iterations: u32, mut solve: F) -> (Option<String>, Duration)
+where
+    F: FnMut() -> Option<String>,
+{
+    let mut answer = None;
+    let start = Instant::now();
+    for _ in 0..iterations {
+        answer = solve();
+    }
+    (answer, start.elapsed() / iterations)
+}
+
+fn report(part: u32, name: &str, answer: Option<String>, time: Duration, expected: Option<&str>) {
+    let answer = answer.unwrap_or_else(|| "<failed>".to_string());
+    let mismatch = match expected {
+        Some(expected) if expected != answer => "  (differs from built-in)",
+        _ => "",
+    };
+    println!(
+        "Part {}  {:<24} {:>12.3?}  {}{}",
+        part, name, time, answer, mismatch
+    );
+}
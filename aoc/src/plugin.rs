@@ -0,0 +1,139 @@
+use std::{
+    ffi::CStr,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use aoc_plugin::{AbiVersionFn, DayFn, FreeFn, SolveFn};
+use libloading::Library;
+
+#[derive(Debug)]
+pub enum PluginError {
+    Io(io::Error),
+    Load(libloading::Error),
+    AbiVersion(u32),
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{}", e),
+            Self::Load(e) => write!(f, "{}", e),
+            Self::AbiVersion(v) => write!(
+                f,
+                "plugin ABI version {} doesn't match runner ABI version {}",
+                v,
+                aoc_plugin::ABI_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PluginError {}
+
+/// A day solver loaded from a shared library at runtime.
+pub struct Plugin {
+    pub path: PathBuf,
+    pub day: u32,
+    solve: SolveFn,
+    free: FreeFn,
+    // keeps the function pointers above valid, so it has to be dropped last
+    _library: Library,
+}
+
+impl Plugin {
+    pub fn load(path: &Path) -> Result<Self, PluginError> {
+        // SAFETY: loading runs the library's initialisers, plugins are trusted code
+        let library = unsafe { Library::new(path) }.map_err(PluginError::Load)?;
+        let (version, day, solve, free) = unsafe {
+            let version = *library
+                .get::<AbiVersionFn>(aoc_plugin::ABI_VERSION_SYMBOL)
+                .map_err(PluginError::Load)?;
+            let day = *library
+                .get::<DayFn>(aoc_plugin::DAY_SYMBOL)
+                .map_err(PluginError::Load)?;
+            let solve = *library
+                .get::<SolveFn>(aoc_plugin::SOLVE_SYMBOL)
+                .map_err(PluginError::Load)?;
+            let free = *library
+                .get::<FreeFn>(aoc_plugin::FREE_SYMBOL)
+                .map_err(PluginError::Load)?;
+            (version(), day(), solve, free)
+        };
+        if version != aoc_plugin::ABI_VERSION {
+            return Err(PluginError::AbiVersion(version));
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            day,
+            solve,
+            free,
+            _library: library,
+        })
+    }
+
+    /// Returns None if the plugin rejected the part or input, or panicked
+    pub fn solve(&self, part: u32, input: &str) -> Option<String> {
+        unsafe {
+            let answer = (self.solve)(part, input.as_ptr(), input.len());
+            if answer.is_null() {
+                return None;
+            }
+            let s = CStr::from_ptr(answer).to_string_lossy().into_owned();
+            (self.free)(answer);
+            Some(s)
+        }
+    }
+
+    pub fn name(&self) -> String {
+        self.path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+}
+
+type LoadResult = Result<Plugin, (PathBuf, PluginError)>;
+
+/// Loads every shared library in `dir`, sorted by file name
+pub fn load_dir(dir: &Path) -> Result<Vec<LoadResult>, PluginError> {
+    let mut paths = fs::read_dir(dir)
+        .map_err(PluginError::Io)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == std::env::consts::DLL_EXTENSION)
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+    Ok(paths
+        .into_iter()
+        .map(|path| Plugin::load(&path).map_err(|e| (path, e)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn not_a_library() {
+        let dir = std::env::temp_dir().join(format!("aoc-plugins-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("broken.{}", std::env::consts::DLL_EXTENSION));
+        fs::write(&path, "not a library").unwrap();
+        fs::write(dir.join("notes.txt"), "ignored").unwrap();
+        let plugins = load_dir(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(plugins.len(), 1);
+        assert!(matches!(&plugins[0], Err((p, PluginError::Load(_))) if p == &path));
+    }
+
+    #[test]
+    fn missing_dir() {
+        assert!(matches!(
+            load_dir(Path::new("/does/not/exist")),
+            Err(PluginError::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,382 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    io::{BufRead, Write},
+    rc::Rc,
+};
+
+/// Interactive state of a single day
+trait Session {
+    /// Day-specific commands, one per line
+    fn help(&self) -> &'static str;
+    /// Renders the current state
+    fn show(&self) -> String;
+    /// Runs a day-specific command and returns the output to print
+    fn command(&mut self, command: &str, args: &[&str]) -> Result<String, String>;
+}
+
+const COMMON_HELP: &str = "show                 print the current state
+help                 print this help
+quit                 leave the repl";
+
+fn session(day: u32, input: &str) -> Result<Box<dyn Session + '_>, String> {
+    match day {
+        11 => Ok(Box::new(Day11::new(input)?)),
+        12 => Ok(Box::new(Day12::new(input)?)),
+        13 => Ok(Box::new(Day13::new(input)?)),
+        16 => Ok(Box::new(Day16::new(input)?)),
+        18 => Ok(Box::new(Day18::new(input)?)),
+        _ => Err(format!(
+            "there is no repl for day {}, try 11, 12, 13, 16 or 18",
+            day
+        )),
+    }
+}
+
+/// Reads commands from `reader` until it's exhausted or `quit` is entered
+pub fn run<R: BufRead, W: Write>(
+    day: u32,
+    input: &str,
+    reader: R,
+    mut writer: W,
+) -> Result<(), String> {
+    let mut session = session(day, input)?;
+    let io_error = |e: std::io::Error| e.to_string();
+    write!(writer, "{}", session.show()).map_err(io_error)?;
+    write!(writer, "day {}> ", day).map_err(io_error)?;
+    writer.flush().map_err(io_error)?;
+    for line in reader.lines() {
+        let line = line.map_err(io_error)?;
+        let mut words = line.split_whitespace();
+        let output = match words.next() {
+            None => Ok(String::new()),
+            Some("quit" | "exit") => break,
+            Some("help") => Ok(format!("{}\n{}\n", session.help(), COMMON_HELP)),
+            Some("show") => Ok(session.show()),
+            Some(command) => session.command(command, &words.collect::<Vec<_>>()),
+        };
+        match output {
+            Ok(output) => write!(writer, "{}", output),
+            Err(e) => writeln!(writer, "error: {}", e),
+        }
+        .map_err(io_error)?;
+        write!(writer, "day {}> ", day).map_err(io_error)?;
+        writer.flush().map_err(io_error)?;
+    }
+    writeln!(writer).map_err(io_error)
+}
+
+fn parse_count(args: &[&str]) -> Result<usize, String> {
+    match args {
+        [] => Ok(1),
+        [n] => n.parse().map_err(|_| format!("'{}' isn't a number", n)),
+        _ => Err("expected at most one argument".to_string()),
+    }
+}
+
+fn unknown(command: &str) -> Result<String, String> {
+    Err(format!("unknown command '{}', try 'help'", command))
+}
+
+struct Day11 {
+    octopuses: day11::Octopuses,
+}
+
+impl Day11 {
+    fn new(input: &str) -> Result<Self, String> {
+        let (_, octopuses) = day11::parse_input(input).map_err(|e| e.to_string())?;
+        Ok(Self { octopuses })
+    }
+}
+
+impl Session for Day11 {
+    fn help(&self) -> &'static str {
+        "step [n]             advance the octopuses n steps (default 1)"
+    }
+
+    fn show(&self) -> String {
+        format!(
+            "step {}, {} flashes in total\n{}",
+            self.octopuses.steps, self.octopuses.flashes, self.octopuses
+        )
+    }
+
+    fn command(&mut self, command: &str, args: &[&str]) -> Result<String, String> {
+        match command {
+            "step" => {
+                let flashed: usize = (0..parse_count(args)?).map(|_| self.octopuses.step()).sum();
+                Ok(format!("{} flashed\n{}", flashed, self.show()))
+            }
+            _ => unknown(command),
+        }
+    }
+}
+
+struct Day12<'a> {
+    graph: day12::Graph<'a>,
+}
+
+impl<'a> Day12<'a> {
+    fn new(input: &'a str) -> Result<Self, String> {
+        let (_, graph) = day12::parse_input(input).map_err(|e| e.to_string())?;
+        Ok(Self { graph })
+    }
+}
+
+impl Session for Day12<'_> {
+    fn help(&self) -> &'static str {
+        "paths <from> <to>    list all paths visiting small caves at most once"
+    }
+
+    fn show(&self) -> String {
+        self.graph.to_string()
+    }
+
+    fn command(&mut self, command: &str, args: &[&str]) -> Result<String, String> {
+        match command {
+            "paths" => {
+                let [from, to] = args else {
+                    return Err("expected a start and an end cave".to_string());
+                };
+                let cave = |name: &str| {
+                    self.graph
+                        .cave(name)
+                        .ok_or(format!("there is no cave '{}'", name))
+                };
+                let (from, to) = (cave(from)?, cave(to)?);
+                let paths = self.graph.paths(from, to);
+                let mut output = paths
+                    .iter()
+                    .map(|path| format!("{}\n", path.join(",")))
+                    .collect::<String>();
+                output.push_str(&format!("{} paths\n", paths.len()));
+                Ok(output)
+            }
+            _ => unknown(command),
+        }
+    }
+}
+
+struct Day13 {
+    paper: day13::Paper,
+    folds: VecDeque<day13::Fold>,
+}
+
+impl Day13 {
+    fn new(input: &str) -> Result<Self, String> {
+        let (_, (points, folds)) = day13::parse_input(input).map_err(|e| e.to_string())?;
+        Ok(Self {
+            paper: day13::Paper::new(points),
+            folds: folds.into(),
+        })
+    }
+
+    fn fold_next(&mut self) -> Result<String, String> {
+        let fold = self.folds.pop_front().ok_or("no folds left")?;
+        self.paper.fold(&fold);
+        Ok(format!("{}\n{}", fold, self.show()))
+    }
+}
+
+impl Session for Day13 {
+    fn help(&self) -> &'static str {
+        "fold next            apply the next fold
+fold all             apply all remaining folds
+folds                list the remaining folds"
+    }
+
+    fn show(&self) -> String {
+        format!(
+            "{} dots, {} folds left\n{}",
+            self.paper.count(),
+            self.folds.len(),
+            self.paper
+        )
+    }
+
+    fn command(&mut self, command: &str, args: &[&str]) -> Result<String, String> {
+        match (command, args) {
+            ("fold", ["next"] | []) => self.fold_next(),
+            ("fold", ["all"]) => {
+                while let Some(fold) = self.folds.pop_front() {
+                    self.paper.fold(&fold);
+                }
+                Ok(self.show())
+            }
+            ("folds", []) => Ok(self
+                .folds
+                .iter()
+                .map(|fold| format!("{}\n", fold))
+                .collect()),
+            _ => unknown(command),
+        }
+    }
+}
+
+struct Day16 {
+    packet: day16::Packet,
+}
+
+impl Day16 {
+    fn new(input: &str) -> Result<Self, String> {
+        let (_, bits) = day16::parse_input(input).map_err(|e| e.to_string())?;
+        let (_, packet) = day16::parse_input_bits(&bits).map_err(|e| e.to_string())?;
+        Ok(Self { packet })
+    }
+}
+
+impl Session for Day16 {
+    fn help(&self) -> &'static str {
+        "versions             sum of all packet versions
+eval                 evaluate the packet expression"
+    }
+
+    fn show(&self) -> String {
+        self.packet.to_string()
+    }
+
+    fn command(&mut self, command: &str, _args: &[&str]) -> Result<String, String> {
+        match command {
+            "versions" => Ok(format!("{}\n", self.packet.version_sum())),
+            "eval" => Ok(format!("{}\n", self.packet.expression())),
+            _ => unknown(command),
+        }
+    }
+}
+
+type Num = Rc<RefCell<day18::Num>>;
+
+struct Day18 {
+    current: Num,
+    remaining: VecDeque<Num>,
+}
+
+impl Day18 {
+    fn new(input: &str) -> Result<Self, String> {
+        let (_, nums) = day18::parse_input(input).map_err(|e| e.to_string())?;
+        let mut remaining: VecDeque<_> = nums.into();
+        let current = remaining.pop_front().ok_or("no numbers in the input")?;
+        Ok(Self { current, remaining })
+    }
+}
+
+impl Session for Day18 {
+    fn help(&self) -> &'static str {
+        "add                  add the next number from the input, without reducing
+set <number>         replace the current number
+explode              explode the leftmost pair that should explode
+split                split the leftmost number that should split
+reduce               explode and split until fully reduced
+magnitude            magnitude of the current number"
+    }
+
+    fn show(&self) -> String {
+        format!(
+            "{}  ({} numbers left)\n",
+            self.current.borrow(),
+            self.remaining.len()
+        )
+    }
+
+    fn command(&mut self, command: &str, args: &[&str]) -> Result<String, String> {
+        match command {
+            "add" => {
+                let next = self.remaining.pop_front().ok_or("no numbers left")?;
+                self.current = day18::add(self.current.clone(), next);
+            }
+            "set" => {
+                let [number] = args else {
+                    return Err("expected a snailfish number".to_string());
+                };
+                let (rem, num) = day18::parse_line(number).map_err(|e| e.to_string())?;
+                if !rem.is_empty() {
+                    return Err(format!("unexpected '{}'", rem));
+                }
+                self.current = num;
+            }
+            "explode" => {
+                if !day18::explode_once(self.current.clone()) {
+                    return Ok(format!("nothing to explode\n{}", self.show()));
+                }
+            }
+            "split" => {
+                if !day18::split(self.current.clone()) {
+                    return Ok(format!("nothing to split\n{}", self.show()));
+                }
+            }
+            "reduce" => self.current = day18::reduce(self.current.clone()),
+            "magnitude" => return Ok(format!("{}\n", self.current.borrow().magnitude())),
+            _ => return unknown(command),
+        }
+        Ok(self.show())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repl(day: u32, input: &str, commands: &str) -> String {
+        let mut output = Vec::new();
+        run(day, input, commands.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn day11() {
+        let output = repl(11, "11111\n19991\n19191\n19991\n11111", "step\nquit\n");
+        assert!(output.contains("9 flashed\nstep 1, 9 flashes in total\n34543\n40004\n"));
+    }
+
+    #[test]
+    fn day12() {
+        let output = repl(
+            12,
+            "start-A\nA-b\nA-end\nb-end",
+            "paths start end\npaths start nowhere\n",
+        );
+        assert!(output.contains("start,A,b,A,end\n"));
+        assert!(output.contains("3 paths\n"));
+        assert!(output.contains("error: there is no cave 'nowhere'\n"));
+    }
+
+    #[test]
+    fn day13() {
+        let output = repl(
+            13,
+            "0,0\n4,0\n1,2\n3,2\n\nfold along x=2",
+            "fold next\nfold next\n",
+        );
+        assert!(output.contains("fold along x=2\n2 dots, 0 folds left\n#.\n..\n.#\n"));
+        assert!(output.contains("error: no folds left\n"));
+    }
+
+    #[test]
+    fn day16() {
+        let output = repl(16, "38006F45291200", "versions\neval\n");
+        assert!(output.starts_with("less than v1\n  literal v6: 10\n"));
+        assert!(output.contains("> 9\n"));
+        assert!(output.contains("> 1\n"));
+    }
+
+    #[test]
+    fn day18() {
+        let output = repl(
+            18,
+            "[[[[4,3],4],4],[7,[[8,4],9]]]\n[1,1]",
+            "add\nexplode\nreduce\nmagnitude\nadd\nfoo\n",
+        );
+        assert!(output.contains("[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]  (0 numbers left)\n"));
+        assert!(output.contains("[[[[0,7],4],[7,[[8,4],9]]],[1,1]]  (0 numbers left)\n"));
+        assert!(output.contains("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]  (0 numbers left)\n"));
+        assert!(output.contains("> 1384\n"));
+        assert!(output.contains("error: no numbers left\n"));
+        assert!(output.contains("error: unknown command 'foo', try 'help'\n"));
+    }
+
+    #[test]
+    fn unsupported() {
+        let mut output = Vec::new();
+        assert!(run(1, "1", "".as_bytes(), &mut output).is_err());
+    }
+}
@@ -0,0 +1,706 @@
+use std::{
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use ratatui::{
+    backend::Backend,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    symbols::Marker,
+    text::{Line, Span},
+    widgets::{
+        canvas::{Canvas, Points, Rectangle},
+        Bar, BarChart, BarGroup, Block, Paragraph,
+    },
+    Frame, Terminal,
+};
+
+/// A single state of a day's simulation
+trait Simulation {
+    fn title(&self) -> &'static str;
+    /// The state after one more step, `None` once the simulation is over
+    fn next(&self) -> Option<Box<dyn Simulation>>;
+    /// Statistics of this step as label and value
+    fn stats(&self) -> Vec<(&'static str, String)>;
+    /// Draws the state into `area`
+    fn render(&self, frame: &mut Frame, area: Rect);
+}
+
+fn simulation(day: u32, input: &str) -> Result<Box<dyn Simulation>, String> {
+    match day {
+        4 => Ok(Box::new(Day4::new(input)?)),
+        6 => Ok(Box::new(Day6::new(input)?)),
+        11 => Ok(Box::new(Day11::new(input)?)),
+        17 => Ok(Box::new(Day17::new(input)?)),
+        _ => Err(format!(
+            "there is no simulation for day {}, try 4, 6, 11 or 17",
+            day
+        )),
+    }
+}
+
+/// Runs the stepper for `day` until it's quit
+pub fn run(day: u32, input: &str) -> Result<(), String> {
+    let mut stepper = Stepper::new(simulation(day, input)?);
+    let mut terminal = ratatui::init();
+    let result = stepper.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+const HELP: &str = "space play/pause  ←/→ step  home start  +/- speed  q quit";
+
+/// Plays a simulation, keeping every state seen so stepping back is free
+struct Stepper {
+    history: Vec<Box<dyn Simulation>>,
+    current: usize,
+    playing: bool,
+    /// Whether the last state in `history` has no successor
+    finished: bool,
+    delay: Duration,
+}
+
+impl Stepper {
+    fn new(start: Box<dyn Simulation>) -> Self {
+        Self {
+            history: vec![start],
+            current: 0,
+            playing: false,
+            finished: false,
+            delay: Duration::from_millis(200),
+        }
+    }
+
+    fn state(&self) -> &dyn Simulation {
+        self.history[self.current].as_ref()
+    }
+
+    /// Moves to the next state, returns false at the end of the simulation
+    fn forward(&mut self) -> bool {
+        if self.current + 1 == self.history.len() {
+            if self.finished {
+                return false;
+            }
+            match self.state().next() {
+                Some(next) => self.history.push(next),
+                None => {
+                    self.finished = true;
+                    return false;
+                }
+            }
+        }
+        self.current += 1;
+        true
+    }
+
+    fn back(&mut self) {
+        self.current = self.current.saturating_sub(1);
+    }
+
+    /// Steps while playing, pausing at the end of the simulation
+    fn tick(&mut self) {
+        if self.playing && !self.forward() {
+            self.playing = false;
+        }
+    }
+
+    /// Handles a key press, returns false to quit
+    fn handle(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char(' ' | 'p') => self.playing = !self.playing,
+            KeyCode::Right | KeyCode::Char('l' | 'n') => {
+                self.playing = false;
+                self.forward();
+            }
+            KeyCode::Left | KeyCode::Char('h' | 'b') => {
+                self.playing = false;
+                self.back();
+            }
+            KeyCode::Home | KeyCode::Char('g') => self.current = 0,
+            KeyCode::Char('+') => self.delay = (self.delay / 2).max(Duration::from_millis(10)),
+            KeyCode::Char('-') => self.delay = (self.delay * 2).min(Duration::from_secs(5)),
+            _ => {}
+        }
+        true
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [view, stats] =
+            Layout::horizontal([Constraint::Min(0), Constraint::Length(30)]).areas(main);
+
+        let state = self.state();
+        let block = Block::bordered().title(state.title());
+        state.render(frame, block.inner(view));
+        frame.render_widget(block, view);
+
+        let lines: Vec<Line> = state
+            .stats()
+            .into_iter()
+            .map(|(label, value)| Line::from(vec![format!("{:<13}", label).bold(), value.into()]))
+            .collect();
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title("stats")),
+            stats,
+        );
+
+        let mode = if self.playing {
+            "▶ playing"
+        } else if self.finished && self.current + 1 == self.history.len() {
+            "■ finished"
+        } else {
+            "⏸ paused"
+        };
+        let status_line = format!(
+            "{} | state {} | {}ms | {}",
+            mode,
+            self.current,
+            self.delay.as_millis(),
+            HELP
+        );
+        frame.render_widget(Paragraph::new(status_line).reversed(), status);
+    }
+
+    fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<(), String> {
+        let io_error = |e: std::io::Error| e.to_string();
+        let mut last_tick = Instant::now();
+        loop {
+            terminal.draw(|frame| self.draw(frame)).map_err(io_error)?;
+            let timeout = if self.playing {
+                self.delay.saturating_sub(last_tick.elapsed())
+            } else {
+                Duration::from_secs(60)
+            };
+            if event::poll(timeout).map_err(io_error)? {
+                if let Event::Key(key) = event::read().map_err(io_error)? {
+                    if key.kind == KeyEventKind::Press && !self.handle(key.code) {
+                        return Ok(());
+                    }
+                }
+            }
+            if self.playing && last_tick.elapsed() >= self.delay {
+                self.tick();
+                last_tick = Instant::now();
+            }
+        }
+    }
+}
+
+fn parse_error<E: std::fmt::Display>(e: E) -> String {
+    e.to_string()
+}
+
+#[derive(Clone)]
+struct Day4 {
+    draws: Rc<day04::Draws>,
+    boards: Rc<day04::Boards>,
+    /// The boards with drawn numbers replaced by 100, like the solver does
+    marked: day04::Boards,
+    drawn: usize,
+    /// Winning boards in order, with their score
+    winners: Vec<(usize, usize)>,
+}
+
+impl Day4 {
+    fn new(input: &str) -> Result<Self, String> {
+        let (_, (draws, boards)) = day04::parse_input(input).map_err(parse_error)?;
+        Ok(Self {
+            draws: Rc::new(draws),
+            marked: boards.clone(),
+            boards: Rc::new(boards),
+            drawn: 0,
+            winners: Vec::new(),
+        })
+    }
+
+    fn won(&self, board: usize) -> bool {
+        self.winners.iter().any(|&(b, _)| b == board)
+    }
+}
+
+impl Simulation for Day4 {
+    fn title(&self) -> &'static str {
+        "Day 4: Giant Squid"
+    }
+
+    fn next(&self) -> Option<Box<dyn Simulation>> {
+        if self.drawn == self.draws.len() || self.winners.len() == self.boards.len() {
+            return None;
+        }
+        let mut next = self.clone();
+        let draw = self.draws[self.drawn];
+        next.drawn += 1;
+        for (i, board) in next.marked.iter_mut().enumerate() {
+            if self.won(i) {
+                continue;
+            }
+            board
+                .iter_mut()
+                .flatten()
+                .filter(|x| **x == draw)
+                .for_each(|x| *x = 100);
+            if day04::bingo(board) {
+                let unmarked: usize = board.iter().flatten().filter(|&&x| x != 100).sum();
+                next.winners.push((i, unmarked * draw));
+            }
+        }
+        Some(Box::new(next))
+    }
+
+    fn stats(&self) -> Vec<(&'static str, String)> {
+        let last = match self.drawn {
+            0 => "-".to_string(),
+            n => self.draws[n - 1].to_string(),
+        };
+        let score = |winner: Option<&(usize, usize)>| {
+            winner.map_or("-".to_string(), |(board, score)| {
+                format!("{} (board {})", score, board + 1)
+            })
+        };
+        vec![
+            ("drawn", format!("{}/{}", self.drawn, self.draws.len())),
+            ("last number", last),
+            (
+                "boards won",
+                format!("{}/{}", self.winners.len(), self.boards.len()),
+            ),
+            ("first winner", score(self.winners.first())),
+            ("last winner", score(self.winners.last())),
+        ]
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect) {
+        let board_width = 5 * 3 + 2;
+        let per_row = (area.width as usize / board_width).max(1);
+        let mut lines = Vec::new();
+        for (row, chunk) in self.boards.chunks(per_row).enumerate() {
+            let first = row * per_row;
+            let mut header = Vec::new();
+            for i in first..first + chunk.len() {
+                let style = if self.won(i) {
+                    Style::new().fg(Color::Green).bold()
+                } else {
+                    Style::new()
+                };
+                header.push(Span::styled(
+                    format!("{:<17}", format!("board {}", i + 1)),
+                    style,
+                ));
+            }
+            lines.push(Line::from(header));
+            let height = chunk.iter().map(|board| board.len()).max().unwrap_or(0);
+            for y in 0..height {
+                let mut spans = Vec::new();
+                for (i, board) in chunk.iter().enumerate() {
+                    for (x, n) in board.get(y).into_iter().flatten().enumerate() {
+                        let span = Span::raw(format!("{:>3}", n));
+                        spans.push(if self.marked[first + i][y][x] == 100 {
+                            span.add_modifier(Modifier::REVERSED)
+                        } else {
+                            span
+                        });
+                    }
+                    spans.push(Span::raw("  "));
+                }
+                lines.push(Line::from(spans));
+            }
+            lines.push(Line::default());
+        }
+        frame.render_widget(Paragraph::new(lines), area);
+    }
+}
+
+struct Day6 {
+    /// Number of fish by timer
+    counts: [u64; 9],
+    day: usize,
+    born: u64,
+}
+
+impl Day6 {
+    /// The puzzle asks about the population after this many days
+    const DAYS: usize = 256;
+
+    fn new(input: &str) -> Result<Self, String> {
+        let (_, fishes) = day06::parse_input(input).map_err(parse_error)?;
+        let mut counts = [0; 9];
+        for fish in fishes {
+            *counts
+                .get_mut(fish.timer)
+                .ok_or(format!("timer {} is too large", fish.timer))? += 1;
+        }
+        Ok(Self {
+            counts,
+            day: 0,
+            born: 0,
+        })
+    }
+}
+
+impl Simulation for Day6 {
+    fn title(&self) -> &'static str {
+        "Day 6: Lanternfish"
+    }
+
+    fn next(&self) -> Option<Box<dyn Simulation>> {
+        if self.day == Self::DAYS {
+            return None;
+        }
+        let mut counts = self.counts;
+        let born = counts[0];
+        counts.rotate_left(1);
+        counts[6] += born;
+        Some(Box::new(Self {
+            counts,
+            day: self.day + 1,
+            born,
+        }))
+    }
+
+    fn stats(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("day", format!("{}/{}", self.day, Self::DAYS)),
+            ("fish", self.counts.iter().sum::<u64>().to_string()),
+            ("born today", self.born.to_string()),
+        ]
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect) {
+        let bars: Vec<Bar> = self
+            .counts
+            .iter()
+            .enumerate()
+            .map(|(timer, &count)| {
+                Bar::default()
+                    .value(count)
+                    .label(Line::from(timer.to_string()))
+            })
+            .collect();
+        let chart = BarChart::default()
+            .data(BarGroup::default().bars(&bars))
+            .bar_width((area.width / 9).saturating_sub(1).max(1))
+            .bar_gap(1)
+            .bar_style(Style::new().fg(Color::Cyan));
+        frame.render_widget(chart, area);
+    }
+}
+
+#[derive(Clone)]
+/// Ends at the first step in which all octopuses flash, or after [`day11::MAX_STEPS`]
+struct Day11 {
+    octopuses: day11::Octopuses,
+    flashed: usize,
+    /// First step in which all octopuses flashed
+    synchronized: Option<usize>,
+}
+
+impl Day11 {
+    fn new(input: &str) -> Result<Self, String> {
+        let (_, octopuses) = day11::parse_input(input).map_err(parse_error)?;
+        Ok(Self {
+            octopuses,
+            flashed: 0,
+            synchronized: None,
+        })
+    }
+}
+
+impl Simulation for Day11 {
+    fn title(&self) -> &'static str {
+        "Day 11: Dumbo Octopus"
+    }
+
+    fn next(&self) -> Option<Box<dyn Simulation>> {
+        if self.synchronized.is_some() || self.octopuses.steps >= day11::MAX_STEPS {
+            return None;
+        }
+        let mut next = self.clone();
+        next.flashed = next.octopuses.step();
+        if next.flashed == next.octopuses.len() && next.synchronized.is_none() {
+            next.synchronized = Some(next.octopuses.steps);
+        }
+        Some(Box::new(next))
+    }
+
+    fn stats(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("step", self.octopuses.steps.to_string()),
+            ("flashed", self.flashed.to_string()),
+            ("flashes", self.octopuses.flashes.to_string()),
+            (
+                "synchronized",
+                self.synchronized
+                    .map_or("-".to_string(), |step| format!("step {}", step)),
+            ),
+        ]
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect) {
+        let grid = self.octopuses.to_string();
+        let lines: Vec<Line> = grid
+            .lines()
+            .map(|row| {
+                Line::from(
+                    row.chars()
+                        .map(|c| match c {
+                            '0' => Span::raw("0").fg(Color::Yellow).bold(),
+                            c => Span::raw(c.to_string()).dim(),
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines), area);
+    }
+}
+
+#[derive(Clone)]
+struct Day17 {
+    area: Rc<day17::Area>,
+    probe: day17::Probe,
+    /// Every position of the probe so far, starting at the launch point
+    path: Vec<day17::Point>,
+    hit: bool,
+}
+
+impl Day17 {
+    /// Launches the probe with the velocity that reaches the highest point, like part 1
+    fn new(input: &str) -> Result<Self, String> {
+        let (_, area) = day17::parse_input(input).map_err(parse_error)?;
+        let launch_point = day17::Point { x: 0, y: 0 };
+        let (x_vel, y_vel) = (1..=area.right())
+            .flat_map(|x_vel| (area.bottom()..area.bottom().abs()).map(move |y_vel| (x_vel, y_vel)))
+            .filter(|&(x_vel, y_vel)| day17::Probe::new(launch_point, x_vel, y_vel).launch(&area))
+            .max_by_key(|&(x_vel, y_vel)| (y_vel, -x_vel))
+            .ok_or("no velocity reaches the target area")?;
+        Ok(Self {
+            area: Rc::new(area),
+            probe: day17::Probe::new(launch_point, x_vel, y_vel),
+            path: vec![launch_point],
+            hit: false,
+        })
+    }
+}
+
+impl Simulation for Day17 {
+    fn title(&self) -> &'static str {
+        "Day 17: Trick Shot"
+    }
+
+    fn next(&self) -> Option<Box<dyn Simulation>> {
+        if self.hit || !self.probe.chance(&self.area) {
+            return None;
+        }
+        let mut next = self.clone();
+        next.probe.step();
+        next.path.push(next.probe.location());
+        next.hit = self.area.contains(next.probe.location());
+        Some(Box::new(next))
+    }
+
+    fn stats(&self) -> Vec<(&'static str, String)> {
+        let status = if self.hit {
+            "hit"
+        } else if self.probe.chance(&self.area) {
+            "flying"
+        } else {
+            "missed"
+        };
+        let (start_x, start_y) = (self.path[0].x, self.path[0].y);
+        let launched = self.path.len() - 1;
+        let highest = self.path.iter().map(|p| p.y).max().unwrap_or(start_y);
+        vec![
+            ("step", launched.to_string()),
+            ("position", format!("{},{}", self.probe.x, self.probe.y)),
+            (
+                "velocity",
+                format!("{},{}", self.probe.x_vel, self.probe.y_vel),
+            ),
+            ("highest", (highest - start_y).to_string()),
+            ("from", format!("{},{}", start_x, start_y)),
+            ("status", status.to_string()),
+        ]
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect) {
+        let xs = self.path.iter().map(|p| p.x);
+        let ys = self.path.iter().map(|p| p.y);
+        let x_bounds = [
+            xs.clone().min().unwrap_or(0).min(self.area.left()) as f64 - 1.0,
+            xs.max().unwrap_or(0).max(self.area.right()) as f64 + 1.0,
+        ];
+        let y_bounds = [
+            ys.clone().min().unwrap_or(0).min(self.area.bottom()) as f64 - 1.0,
+            ys.max().unwrap_or(0).max(self.area.top()) as f64 + 1.0,
+        ];
+        let path: Vec<(f64, f64)> = self.path.iter().map(|p| (p.x as f64, p.y as f64)).collect();
+        let canvas = Canvas::default()
+            .marker(Marker::Braille)
+            .x_bounds(x_bounds)
+            .y_bounds(y_bounds)
+            .paint(|ctx| {
+                ctx.draw(&Rectangle {
+                    x: self.area.left() as f64,
+                    y: self.area.bottom() as f64,
+                    width: (self.area.right() - self.area.left()) as f64,
+                    height: (self.area.top() - self.area.bottom()) as f64,
+                    color: Color::Green,
+                });
+                ctx.draw(&Points {
+                    coords: &path,
+                    color: Color::Yellow,
+                });
+                ctx.print(path[0].0, path[0].1, "S");
+                ctx.print(self.probe.x as f64, self.probe.y as f64, "o".bold());
+            });
+        frame.render_widget(canvas, area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::backend::TestBackend;
+
+    use super::*;
+
+    const WIDTH: u16 = 90;
+
+    fn screen(stepper: &Stepper) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(WIDTH, 24)).unwrap();
+        terminal.draw(|frame| stepper.draw(frame)).unwrap();
+        terminal
+            .backend()
+            .buffer()
+            .content
+            .chunks(WIDTH as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn stepper(day: u32, input: &str) -> Stepper {
+        Stepper::new(simulation(day, input).unwrap())
+    }
+
+    fn stat(stepper: &Stepper, label: &str) -> String {
+        stepper
+            .state()
+            .stats()
+            .into_iter()
+            .find(|(l, _)| *l == label)
+            .unwrap()
+            .1
+    }
+
+    #[test]
+    fn octopuses() {
+        let mut stepper = stepper(11, "11111\n19991\n19191\n19991\n11111");
+        let start = screen(&stepper);
+        assert!(start.contains("Day 11: Dumbo Octopus"));
+        assert!(start.contains("19991"));
+        assert!(stepper.forward());
+        let screen = screen(&stepper);
+        assert!(screen.contains("34543"));
+        assert!(screen.contains("flashed      9"));
+        stepper.back();
+        assert_eq!(super::tests::screen(&stepper), start);
+        assert_eq!(stepper.history.len(), 2);
+    }
+
+    #[test]
+    fn synchronized() {
+        let mut stepper = stepper(
+            11,
+            "5483143223\n2745854711\n5264556173\n6141336146\n6357385478\n4167524645\n2176841721\n6882881134\n4846848554\n5283751526",
+        );
+        while stepper.forward() {}
+        assert_eq!(stat(&stepper, "synchronized"), "step 195");
+        assert_eq!(stepper.history.len(), 196);
+        assert!(!stepper.forward());
+    }
+
+    #[test]
+    fn bingo() {
+        let input = "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
+
+22 13 17 11  0
+ 8  2 23  4 24
+21  9 14 16  7
+ 6 10  3 18  5
+ 1 12 20 15 19
+
+ 3 15  0  2 22
+ 9 18 13 17  5
+19  8  7 25 23
+20 11 10 24  4
+14 21 16 12  6
+
+14 21 17 24  4
+10 16 15  9 19
+18  8 23 26 20
+22 11 13  6  5
+ 2  0 12  3  7";
+        let mut stepper = stepper(4, input);
+        assert!(screen(&stepper).contains("board 3"));
+        for _ in 0..12 {
+            assert!(stepper.forward());
+        }
+        assert_eq!(stat(&stepper, "first winner"), "4512 (board 3)");
+        assert_eq!(stat(&stepper, "last number"), "24");
+        stepper.playing = true;
+        while stepper.playing {
+            stepper.tick();
+        }
+        assert_eq!(stat(&stepper, "last winner"), "1924 (board 2)");
+        assert!(screen(&stepper).contains("■ finished"));
+    }
+
+    #[test]
+    fn fish() {
+        let mut stepper = stepper(6, "3,4,3,1,2");
+        for _ in 0..18 {
+            stepper.forward();
+        }
+        assert_eq!(stat(&stepper, "fish"), "26");
+        while stepper.forward() {}
+        assert_eq!(stat(&stepper, "fish"), "26984457539");
+        assert_eq!(stepper.current, 256);
+        assert!(screen(&stepper).contains("day          256/256"));
+    }
+
+    #[test]
+    fn probe() {
+        let mut stepper = stepper(17, "target area: x=20..30, y=-10..-5");
+        assert_eq!(stat(&stepper, "velocity"), "6,9");
+        while stepper.forward() {}
+        assert_eq!(stat(&stepper, "status"), "hit");
+        assert_eq!(stat(&stepper, "highest"), "45");
+        assert!(screen(&stepper).contains("Day 17: Trick Shot"));
+    }
+
+    #[test]
+    fn keys() {
+        let mut stepper = stepper(6, "3");
+        assert!(stepper.handle(KeyCode::Char(' ')));
+        assert!(stepper.playing);
+        assert!(screen(&stepper).contains("▶ playing"));
+        assert!(stepper.handle(KeyCode::Right));
+        assert!(!stepper.playing);
+        assert!(stepper.handle(KeyCode::Left));
+        assert!(stepper.handle(KeyCode::Left));
+        assert_eq!(stepper.current, 0);
+        assert!(stepper.handle(KeyCode::Char('+')));
+        assert_eq!(stepper.delay, Duration::from_millis(100));
+        assert!(!stepper.handle(KeyCode::Char('q')));
+    }
+
+    #[test]
+    fn unsupported() {
+        assert!(simulation(1, "199").is_err());
+        assert!(simulation(17, "target area: x=20..30").is_err());
+    }
+}
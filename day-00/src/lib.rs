@@ -1,3 +1,6 @@
+//! Day 0: Template
+
+use aoc_parse::{blank_line_separated, comma_list, digit_grid, lines_of, signed, unsigned};
 use nom::{
     branch::alt,
     bytes::complete::tag,
@@ -8,22 +11,27 @@ use nom::{
     IResult,
 };
 
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(0);
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part1(input: &str) -> String {
     "".to_string()
 }
 
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part2(input: &str) -> String {
     "".to_string()
 }
 
 type Line<'a> = Vec<&'a str>;
 
-fn parse_input(input: &str) -> IResult<&str, Vec<Line>> {
-    let (input, lines) = separated_list1(newline, parse_line)(input)?;
-    Ok((input, lines))
+/// Parses one line of words per line
+pub fn parse_input(input: &str) -> IResult<&str, Vec<Line<'_>>> {
+    lines_of(parse_line)(input)
 }
 
-fn parse_line(input: &str) -> IResult<&str, Line> {
+fn parse_line(input: &str) -> IResult<&str, Line<'_>> {
     let (input, line) = separated_list1(space1, alpha1)(input)?;
     Ok((input, line))
 }
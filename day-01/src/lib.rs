@@ -1,45 +1,192 @@
-use nom::{
-    character::complete::digit1, character::complete::newline, multi::separated_list1, IResult,
-};
+//! Day 1: Sonar Sweep
 
+use std::{collections::VecDeque, fmt::Display};
+
+use aoc_parse::{lines_of, unsigned};
+use nom::IResult;
+
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(1);
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part1(input: &str) -> String {
     let (_, depths) = parse_input(input).unwrap();
-    let increased: usize = depths
-        .windows(2)
-        .map(|window| if window[0] < window[1] { 1 } else { 0 })
-        .sum();
-    increased.to_string()
+    #[cfg(feature = "trace")]
+    tracing::debug!(readings = depths.len(), "parsed");
+    count_increases(depths.into_iter(), 1).to_string()
 }
 
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part2(input: &str) -> String {
     let (_, depths) = parse_input(input).unwrap();
-    let mut last_sum = depths[0] + depths[1] + depths[2];
-    let increased_window: usize = depths
-        .windows(3)
-        .map(|window| {
-            let new_sum: usize = window.iter().sum();
-            if new_sum > last_sum {
-                last_sum = new_sum;
-                1
-            } else {
-                last_sum = new_sum;
-                0
+    #[cfg(feature = "trace")]
+    tracing::debug!(readings = depths.len(), "parsed");
+    count_increases(depths.into_iter(), 3).to_string()
+}
+
+/// Number of times the sum of `window` consecutive depths is larger than the one before.
+///
+/// Two neighbouring windows share all but their first and last depth, so only
+/// the last `window` depths are kept in memory.
+pub fn count_increases<I: Iterator<Item = u64>>(depths: I, window: usize) -> usize {
+    if window == 0 {
+        return 0;
+    }
+    let mut recent = VecDeque::with_capacity(window + 1);
+    let mut increases = 0;
+    for depth in depths {
+        recent.push_back(depth);
+        if recent.len() > window {
+            let dropped = recent.pop_front().unwrap();
+            if depth > dropped {
+                increases += 1;
             }
-        })
-        .sum();
-    increased_window.to_string()
+        }
+    }
+    increases
+}
+
+/// Readings that follow each other, from `start` up to and including `end`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Run {
+    pub start: usize,
+    pub end: usize,
 }
 
-type Line = usize;
+impl Run {
+    /// Number of readings in the run
+    pub fn readings(&self) -> usize {
+        self.end - self.start + 1
+    }
 
-fn parse_input(input: &str) -> IResult<&str, Vec<Line>> {
-    let (input, lines) = separated_list1(newline, parse_line)(input)?;
-    Ok((input, lines))
+    /// Keeps the longer run, or the earlier one if they're as long
+    fn longest(run: Option<Run>, other: Run) -> Option<Run> {
+        match run {
+            Some(run) if run.readings() >= other.readings() => Some(run),
+            _ => Some(other),
+        }
+    }
 }
 
-fn parse_line(input: &str) -> IResult<&str, Line> {
-    let (input, line) = digit1(input)?;
-    Ok((input, line.parse().unwrap()))
+/// How the depth changes over a sweep, with positions as 0-based indices into the readings
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trend {
+    pub readings: usize,
+    /// Longest run of strictly increasing readings, the first one on a tie
+    pub longest_increase: Option<Run>,
+    /// Longest run of strictly decreasing readings, the first one on a tie
+    pub longest_decrease: Option<Run>,
+    /// Largest decrease from one reading to the next, as (position, size)
+    pub largest_drop: Option<(usize, u64)>,
+    /// Positions of the readings that are deeper than the one before
+    pub increases: Vec<usize>,
+    pub window: usize,
+    /// Average of every `window` consecutive readings
+    pub moving_average: Vec<f64>,
+}
+
+/// The trend of `depths`, in a single pass that keeps only the last `window` readings
+pub fn trend<I: IntoIterator<Item = u64>>(depths: I, window: usize) -> Trend {
+    let mut trend = Trend {
+        readings: 0,
+        longest_increase: None,
+        longest_decrease: None,
+        largest_drop: None,
+        increases: Vec::new(),
+        window,
+        moving_average: Vec::new(),
+    };
+    let mut previous = None;
+    let (mut rising, mut falling) = (0, 0);
+    let mut recent = VecDeque::with_capacity(window);
+    let mut sum = 0;
+    for (i, depth) in depths.into_iter().enumerate() {
+        trend.readings += 1;
+        if let Some(previous) = previous {
+            if depth > previous {
+                trend.increases.push(i);
+                trend.longest_increase = Run::longest(
+                    trend.longest_increase,
+                    Run {
+                        start: rising,
+                        end: i,
+                    },
+                );
+            } else {
+                rising = i;
+            }
+            if depth < previous {
+                let drop = previous - depth;
+                if trend.largest_drop.is_none_or(|(_, largest)| drop > largest) {
+                    trend.largest_drop = Some((i, drop));
+                }
+                trend.longest_decrease = Run::longest(
+                    trend.longest_decrease,
+                    Run {
+                        start: falling,
+                        end: i,
+                    },
+                );
+            } else {
+                falling = i;
+            }
+        }
+        previous = Some(depth);
+
+        if window > 0 {
+            recent.push_back(depth);
+            sum += depth;
+            if recent.len() > window {
+                sum -= recent.pop_front().unwrap();
+            }
+            if recent.len() == window {
+                trend.moving_average.push(sum as f64 / window as f64);
+            }
+        }
+    }
+    trend
+}
+
+impl Display for Trend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let run = |run: Option<Run>| match run {
+            Some(run) => format!("{} readings, {} to {}", run.readings(), run.start, run.end),
+            None => "none".to_string(),
+        };
+        let list = |values: Vec<String>| values.join(", ");
+        writeln!(f, "readings          {}", self.readings)?;
+        writeln!(f, "longest increase  {}", run(self.longest_increase))?;
+        writeln!(f, "longest decrease  {}", run(self.longest_decrease))?;
+        match self.largest_drop {
+            Some((position, drop)) => writeln!(f, "largest drop      {} at {}", drop, position)?,
+            None => writeln!(f, "largest drop      none")?,
+        }
+        writeln!(
+            f,
+            "increases         {}: {}",
+            self.increases.len(),
+            list(self.increases.iter().map(|i| i.to_string()).collect())
+        )?;
+        writeln!(
+            f,
+            "moving average    window {}: {}",
+            self.window,
+            list(
+                self.moving_average
+                    .iter()
+                    .map(|a| format!("{:.2}", a))
+                    .collect()
+            )
+        )
+    }
+}
+
+/// A single depth measurement
+pub type Line = u64;
+
+/// Parses one depth measurement per line
+pub fn parse_input(input: &str) -> IResult<&str, Vec<Line>> {
+    lines_of(unsigned)(input)
 }
 
 #[cfg(test)]
@@ -77,4 +224,44 @@ mod tests {
         let result = process_part2(input);
         assert_eq!(result, "5");
     }
+
+    #[test]
+    fn windows() {
+        let depths = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+        assert_eq!(count_increases(depths.into_iter(), 1), 7);
+        assert_eq!(count_increases(depths.into_iter(), 3), 5);
+        assert_eq!(count_increases(depths.into_iter(), 10), 0);
+        assert_eq!(count_increases(depths.into_iter(), 0), 0);
+        assert_eq!(count_increases([1, 2].into_iter(), 3), 0);
+        assert_eq!(count_increases(std::iter::empty(), 3), 0);
+        assert_eq!(process_part2("1\n2"), "0");
+        // streams without ever holding more than the window
+        assert_eq!(count_increases((0..1_000_000).map(|i| i % 7), 2), 714_285);
+    }
+
+    #[test]
+    fn trends() {
+        let depths = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+        let trend = trend(depths, 3);
+        assert_eq!(trend.readings, 10);
+        assert_eq!(trend.longest_increase, Some(Run { start: 0, end: 3 }));
+        assert_eq!(trend.longest_decrease, Some(Run { start: 3, end: 4 }));
+        assert_eq!(trend.largest_drop, Some((4, 10)));
+        assert_eq!(trend.increases, vec![1, 2, 3, 5, 6, 7, 9]);
+        let sums: Vec<f64> = trend.moving_average.iter().map(|a| a * 3.0).collect();
+        assert_eq!(
+            sums,
+            vec![607.0, 618.0, 618.0, 617.0, 647.0, 716.0, 769.0, 792.0]
+        );
+        assert!(trend
+            .to_string()
+            .contains("longest increase  4 readings, 0 to 3\n"));
+
+        let flat = super::trend([5, 5, 4, 3, 3], 0);
+        assert_eq!(flat.longest_increase, None);
+        assert_eq!(flat.longest_decrease, Some(Run { start: 1, end: 3 }));
+        assert_eq!(flat.largest_drop, Some((2, 1)));
+        assert!(flat.moving_average.is_empty());
+        assert_eq!(super::trend([], 3).readings, 0);
+    }
 }
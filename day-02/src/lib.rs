@@ -1,47 +1,189 @@
+//! Day 2: Dive!
+
+use std::fmt::Display;
+
+use aoc_parse::{lines_of, unsigned};
 use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::char as nomchar,
-    character::complete::{digit1, newline},
-    multi::separated_list1,
-    sequence::tuple,
+    branch::alt, bytes::complete::tag, character::complete::char as nomchar, sequence::tuple,
     IResult,
 };
 
+pub mod steering;
+
+use steering::{Aim, Classic, SteeringModel};
+
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(2);
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part1(input: &str) -> String {
     let (_, instructions) = parse_input(input).unwrap();
-    let mut depth = 0;
-    let mut position = 0;
-    instructions
-        .into_iter()
-        .for_each(|instruction| match instruction {
-            Instruction::Forward(x) => position += x,
-            Instruction::Up(x) => depth -= x,
-            Instruction::Down(x) => depth += x,
-        });
-    (depth * position).to_string()
+    let sub = simulate(&Classic, &instructions, Surface::Breach).submarine();
+    #[cfg(feature = "trace")]
+    tracing::debug!(sub.position, sub.depth, "course done");
+    (sub.depth * sub.position).to_string()
 }
 
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part2(input: &str) -> String {
     let (_, instructions) = parse_input(input).unwrap();
-    let mut depth = 0;
-    let mut position = 0;
-    let mut aim = 0;
-    instructions
-        .into_iter()
-        .for_each(|instruction| match instruction {
-            Instruction::Forward(x) => {
-                position += x;
-                depth += x * aim;
-            }
-            Instruction::Up(x) => aim -= x,
-            Instruction::Down(x) => aim += x,
-        });
-    (depth * position).to_string()
+    let sub = simulate(&Aim, &instructions, Surface::Breach).submarine();
+    #[cfg(feature = "trace")]
+    tracing::debug!(sub.position, sub.depth, sub.aim, "course done");
+    (sub.depth * sub.position).to_string()
+}
+
+/// Where the submarine is, negative depths are above the surface
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Submarine {
+    pub position: i64,
+    pub depth: i64,
+    pub aim: i64,
+}
+
+/// What happens when a command takes the submarine above the surface
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Surface {
+    /// The submarine keeps going, to a negative depth
+    Breach,
+    /// The submarine stops at depth 0
+    Clamp,
+}
+
+/// A command that took the submarine from below to above the surface
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Breach {
+    /// Number of the command, counting from 1
+    pub command: usize,
+    /// Depth the command would have taken the submarine to
+    pub depth: i64,
+}
+
+impl Display for Breach {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "breached surface at command {}", self.command)
+    }
+}
+
+/// The submarine after every command of a course, with every breach on the way
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dive {
+    pub trajectory: Vec<Submarine>,
+    pub breaches: Vec<Breach>,
+}
+
+impl Dive {
+    /// Where the course ended
+    pub fn submarine(&self) -> Submarine {
+        self.trajectory.last().copied().unwrap_or_default()
+    }
+
+    /// The trajectory as `command,position,depth,aim` rows, starting from the surface
+    pub fn csv(&self) -> String {
+        let mut csv = "command,position,depth,aim\n".to_string();
+        let start = std::iter::once(Submarine::default());
+        for (i, sub) in start.chain(self.trajectory.iter().copied()).enumerate() {
+            csv += &format!("{},{},{},{}\n", i, sub.position, sub.depth, sub.aim);
+        }
+        csv
+    }
+
+    /// The depth over the horizontal position as an SVG plot, `width` by `height` pixels
+    pub fn svg(&self, width: u32, height: u32) -> String {
+        let start = std::iter::once(Submarine::default());
+        let points: Vec<Submarine> = start.chain(self.trajectory.iter().copied()).collect();
+        let max_x = points.iter().map(|p| p.position).max().unwrap_or(0).max(1);
+        let min_y = points.iter().map(|p| p.depth).min().unwrap_or(0).min(0);
+        let max_y = points
+            .iter()
+            .map(|p| p.depth)
+            .max()
+            .unwrap_or(0)
+            .max(min_y + 1);
+        let line: Vec<String> = points
+            .iter()
+            .map(|p| format!("{},{}", p.position, p.depth))
+            .collect();
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 {} {} {}\" preserveAspectRatio=\"none\">\n",
+            width,
+            height,
+            min_y,
+            max_x,
+            max_y - min_y
+        );
+        svg += &format!(
+            "  <line x1=\"0\" y1=\"0\" x2=\"{}\" y2=\"0\" stroke=\"steelblue\" vector-effect=\"non-scaling-stroke\"/>\n",
+            max_x
+        );
+        svg += &format!(
+            "  <polyline points=\"{}\" fill=\"none\" stroke=\"black\" vector-effect=\"non-scaling-stroke\"/>\n",
+            line.join(" ")
+        );
+        svg += "</svg>\n";
+        svg
+    }
+}
+
+/// Follows `instructions` steering with `model`
+pub fn simulate(model: &dyn SteeringModel, instructions: &[Instruction], surface: Surface) -> Dive {
+    let mut dive = Dive::default();
+    let mut sub = Submarine::default();
+    for (i, &instruction) in instructions.iter().enumerate() {
+        let mut next = model.steer(sub, instruction);
+        if sub.depth >= 0 && next.depth < 0 {
+            let breach = Breach {
+                command: i + 1,
+                depth: next.depth,
+            };
+            #[cfg(feature = "trace")]
+            tracing::debug!(breach.command, breach.depth, "breached surface");
+            dive.breaches.push(breach);
+        }
+        if surface == Surface::Clamp {
+            next.depth = next.depth.max(0);
+        }
+        sub = next;
+        #[cfg(feature = "trace")]
+        tracing::trace!(?instruction, sub.position, sub.depth, sub.aim, "step");
+        dive.trajectory.push(sub);
+    }
+    dive
 }
 
-#[derive(Debug, PartialEq, Eq)]
-enum Instruction {
+/// The shortest course from the surface to `position` and `depth` when steering with
+/// `model`, checked by following it
+pub fn plan(
+    model: &dyn SteeringModel,
+    position: i64,
+    depth: i64,
+) -> Result<Vec<Instruction>, String> {
+    let course = model.plan(position, depth).ok_or(format!(
+        "{} can't plan a course to ({}, {})",
+        model.name(),
+        position,
+        depth
+    ))?;
+    let end = simulate(model, &course, Surface::Breach).submarine();
+    if (end.position, end.depth) != (position, depth) {
+        return Err(format!(
+            "{} planned a course to ({}, {}) instead of ({}, {})",
+            model.name(),
+            end.position,
+            end.depth,
+            position,
+            depth
+        ));
+    }
+    #[cfg(feature = "trace")]
+    tracing::debug!(model = model.name(), commands = course.len(), "planned");
+    Ok(course)
+}
+
+/// A single submarine command with its amount
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Instruction {
     Forward(usize),
     Down(usize),
     Up(usize),
@@ -49,17 +191,17 @@ enum Instruction {
 
 type Line = Instruction;
 
-fn parse_input(input: &str) -> IResult<&str, Vec<Line>> {
-    let (input, lines) = separated_list1(newline, parse_line)(input)?;
-    Ok((input, lines))
+/// Parses one `forward`, `down` or `up` command per line
+pub fn parse_input(input: &str) -> IResult<&str, Vec<Line>> {
+    lines_of(parse_line)(input)
 }
 
 fn parse_line(input: &str) -> IResult<&str, Line> {
-    let (input, (dir, _, amount)) = tuple((parse_direction, nomchar(' '), digit1))(input)?;
+    let (input, (dir, _, amount)) = tuple((parse_direction, nomchar(' '), unsigned))(input)?;
     let direction = match dir {
-        "forward" => Instruction::Forward(amount.parse().unwrap()),
-        "down" => Instruction::Down(amount.parse().unwrap()),
-        "up" => Instruction::Up(amount.parse().unwrap()),
+        "forward" => Instruction::Forward(amount),
+        "down" => Instruction::Down(amount),
+        "up" => Instruction::Up(amount),
         _ => unreachable!(),
     };
     Ok((input, direction))
@@ -96,4 +238,104 @@ forward 2";
         let result = process_part2(input);
         assert_eq!(result, "900");
     }
+
+    #[test]
+    fn surface() {
+        let (_, instructions) =
+            parse_input("down 2\nforward 1\nup 5\nforward 1\nup 1\ndown 4").unwrap();
+        let dive = simulate(&Classic, &instructions, Surface::Breach);
+        assert_eq!(dive.submarine().depth, 0);
+        assert_eq!(
+            dive.breaches,
+            vec![Breach {
+                command: 3,
+                depth: -3
+            }]
+        );
+        assert_eq!(
+            dive.breaches[0].to_string(),
+            "breached surface at command 3"
+        );
+        let dive = simulate(&Classic, &instructions, Surface::Clamp);
+        assert_eq!(dive.submarine().depth, 4);
+        assert_eq!(
+            dive.breaches.iter().map(|b| b.command).collect::<Vec<_>>(),
+            vec![3, 5]
+        );
+        assert_eq!(process_part1("up 3\nforward 2"), "-6");
+    }
+
+    #[test]
+    fn surface_aimed() {
+        let (_, instructions) =
+            parse_input("down 1\nforward 3\nup 2\nforward 5\nforward 1").unwrap();
+        let dive = simulate(&Aim, &instructions, Surface::Breach);
+        assert_eq!(
+            dive.submarine(),
+            Submarine {
+                position: 9,
+                depth: -3,
+                aim: -1
+            }
+        );
+        assert_eq!(
+            dive.breaches,
+            vec![Breach {
+                command: 4,
+                depth: -2
+            }]
+        );
+        let dive = simulate(&Aim, &instructions, Surface::Clamp);
+        assert_eq!(dive.submarine().depth, 0);
+        assert_eq!(dive.breaches.len(), 2);
+    }
+
+    #[test]
+    fn export() {
+        let (_, instructions) = parse_input("forward 5\ndown 5\nforward 8\nup 3").unwrap();
+        let dive = simulate(&Aim, &instructions, Surface::Breach);
+        assert_eq!(dive.trajectory.len(), 4);
+        assert_eq!(
+            dive.csv(),
+            "command,position,depth,aim\n0,0,0,0\n1,5,0,0\n2,5,0,5\n3,13,40,5\n4,13,40,2\n"
+        );
+        let svg = dive.svg(800, 400);
+        assert!(svg.contains("viewBox=\"0 0 13 40\""));
+        assert!(svg.contains("points=\"0,0 5,0 5,0 13,40 13,40\""));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn planning() {
+        use Instruction::*;
+        assert_eq!(plan(&Classic, 15, 10), Ok(vec![Forward(15), Down(10)]));
+        assert_eq!(plan(&Classic, 0, -3), Ok(vec![Up(3)]));
+        assert_eq!(plan(&Aim, 15, 60), Ok(vec![Down(4), Forward(15)]));
+        assert_eq!(
+            plan(&Aim, 15, 62),
+            Ok(vec![Forward(14), Down(62), Forward(1)])
+        );
+        assert_eq!(plan(&Aim, 4, -7), Ok(vec![Forward(3), Up(7), Forward(1)]));
+        assert_eq!(
+            plan(&Aim, 2_000_000_001, 1_000_000_007),
+            Ok(vec![
+                Forward(2_000_000_000),
+                Down(1_000_000_007),
+                Forward(1)
+            ])
+        );
+        assert_eq!(plan(&Aim, i64::MAX, i64::MAX - 1).map(|c| c.len()), Ok(3));
+        assert_eq!(plan(&Aim, 0, 0), Ok(vec![]));
+        assert!(plan(&Aim, 0, 5).is_err());
+        assert!(plan(&Classic, -1, 0).is_err());
+        for position in 0..20 {
+            for depth in -50..50 {
+                assert!(plan(&Classic, position, depth).is_ok());
+                assert_eq!(
+                    plan(&Aim, position, depth).is_ok(),
+                    position > 0 || depth == 0
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,170 @@
+//! How the submarine interprets its commands.
+//!
+//! A [`SteeringModel`] turns one command into the next state of the
+//! submarine. [`Classic`] and [`Aim`] are the two models from the puzzle,
+//! [`Models`] finds a model by name and takes new ones with [`Models::register`].
+//! Models that know how to reach a target also plan the shortest course there.
+
+use crate::{Instruction, Submarine};
+
+/// A way of steering the submarine
+pub trait SteeringModel {
+    /// Name to look the model up by
+    fn name(&self) -> &str;
+
+    /// The submarine after following `instruction`
+    fn steer(&self, sub: Submarine, instruction: Instruction) -> Submarine;
+
+    /// The fewest commands that take the submarine from the surface to `position` and
+    /// `depth`, `None` if it can't get there or the model doesn't plan courses
+    fn plan(&self, _position: i64, _depth: i64) -> Option<Vec<Instruction>> {
+        None
+    }
+}
+
+/// `up` or `down` by `amount`, nothing for 0
+fn vertical(amount: i64) -> Option<Instruction> {
+    match amount {
+        0 => None,
+        a if a > 0 => Some(Instruction::Down(a as usize)),
+        a => Some(Instruction::Up(a.unsigned_abs() as usize)),
+    }
+}
+
+/// `down` and `up` change the depth directly
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Classic;
+
+impl SteeringModel for Classic {
+    fn name(&self) -> &str {
+        "classic"
+    }
+
+    fn steer(&self, mut sub: Submarine, instruction: Instruction) -> Submarine {
+        match instruction {
+            Instruction::Forward(x) => sub.position += x as i64,
+            Instruction::Down(x) => sub.depth += x as i64,
+            Instruction::Up(x) => sub.depth -= x as i64,
+        }
+        sub
+    }
+
+    fn plan(&self, position: i64, depth: i64) -> Option<Vec<Instruction>> {
+        if position < 0 {
+            return None;
+        }
+        let forward = (position > 0).then_some(Instruction::Forward(position as usize));
+        Some(forward.into_iter().chain(vertical(depth)).collect())
+    }
+}
+
+/// `down` and `up` change the aim, `forward` dives along it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Aim;
+
+impl SteeringModel for Aim {
+    fn name(&self) -> &str {
+        "aim"
+    }
+
+    fn steer(&self, mut sub: Submarine, instruction: Instruction) -> Submarine {
+        match instruction {
+            Instruction::Forward(x) => {
+                sub.position += x as i64;
+                sub.depth += x as i64 * sub.aim;
+            }
+            Instruction::Down(x) => sub.aim += x as i64,
+            Instruction::Up(x) => sub.aim -= x as i64,
+        }
+        sub
+    }
+
+    /// Only `forward` changes the depth, by its distance times the aim. If the
+    /// distance doesn't divide the depth, the last step of it is taken with the
+    /// whole depth as aim.
+    fn plan(&self, position: i64, depth: i64) -> Option<Vec<Instruction>> {
+        if position < 0 || (position == 0 && depth != 0) {
+            return None;
+        }
+        if depth % position.max(1) == 0 {
+            let aim = vertical(depth / position.max(1));
+            let forward = (position > 0).then_some(Instruction::Forward(position as usize));
+            return Some(aim.into_iter().chain(forward).collect());
+        }
+        Some(vec![
+            Instruction::Forward((position - 1) as usize),
+            vertical(depth)?,
+            Instruction::Forward(1),
+        ])
+    }
+}
+
+/// Steering models by name, starting with the built-in ones
+pub struct Models {
+    models: Vec<Box<dyn SteeringModel>>,
+}
+
+impl Default for Models {
+    fn default() -> Self {
+        Self {
+            models: vec![Box::new(Classic), Box::new(Aim)],
+        }
+    }
+}
+
+impl Models {
+    /// Adds `model`, replacing a model with the same name
+    pub fn register(&mut self, model: Box<dyn SteeringModel>) {
+        self.models.retain(|m| m.name() != model.name());
+        self.models.push(model);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn SteeringModel> {
+        self.models
+            .iter()
+            .find(|m| m.name() == name)
+            .map(|m| m.as_ref())
+    }
+
+    /// Names of all models, in the order they were registered
+    pub fn names(&self) -> Vec<&str> {
+        self.models.iter().map(|m| m.name()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `forward` also drags the submarine down by half its distance
+    struct Sinking;
+
+    impl SteeringModel for Sinking {
+        fn name(&self) -> &str {
+            "sinking"
+        }
+
+        fn steer(&self, sub: Submarine, instruction: Instruction) -> Submarine {
+            let mut sub = Classic.steer(sub, instruction);
+            if let Instruction::Forward(x) = instruction {
+                sub.depth += x as i64 / 2;
+            }
+            sub
+        }
+    }
+
+    #[test]
+    fn registry() {
+        let mut models = Models::default();
+        assert_eq!(models.names(), vec!["classic", "aim"]);
+        models.register(Box::new(Sinking));
+        models.register(Box::new(Aim));
+        assert_eq!(models.names(), vec!["classic", "sinking", "aim"]);
+        let sub = models
+            .get("sinking")
+            .unwrap()
+            .steer(Submarine::default(), Instruction::Forward(4));
+        assert_eq!(sub.depth, 2);
+        assert!(models.get("ballast").is_none());
+    }
+}
@@ -0,0 +1,327 @@
+//! Diagnostic reports packed into bits.
+//!
+//! Digits of base `k` take the fewest bits that hold `k - 1`, as many to a
+//! `u64` word as fit whole. Every line is stored as `stride` consecutive words,
+//! a single word for binary reports up to 64 digits wide. How often each digit
+//! occurs in each column is counted once while packing.
+
+use std::fmt::Display;
+
+use crate::trie::Criterion;
+
+/// Bits per digit and digits per word for digits of `base`
+fn packing(base: u32) -> (usize, usize) {
+    let bits = (u32::BITS - (base - 1).leading_zeros()).max(1) as usize;
+    (bits, 64 / bits)
+}
+
+/// Word and shift holding `column` of a line that's `width` digits of `base` wide
+fn position(base: u32, width: usize, column: usize) -> (usize, usize) {
+    let (bits, per_word) = packing(base);
+    let index = width - 1 - column;
+    (index / per_word, index % per_word * bits)
+}
+
+/// Number of words for a line that's `width` digits of `base` wide
+fn stride(base: u32, width: usize) -> usize {
+    width.div_ceil(packing(base).1).max(1)
+}
+
+/// Packed digits, the last digit in the lowest bits of the first word
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Digits {
+    base: u32,
+    width: usize,
+    words: Vec<u64>,
+}
+
+impl Digits {
+    fn new(base: u32, width: usize) -> Self {
+        Self {
+            base,
+            width,
+            words: vec![0; stride(base, width)],
+        }
+    }
+
+    pub fn base(&self) -> u32 {
+        self.base
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Digit in `column`, counting from the left
+    pub fn get(&self, column: usize) -> usize {
+        let (word, shift) = position(self.base, self.width, column);
+        let mask = (1 << packing(self.base).0) - 1;
+        (self.words[word] >> shift & mask) as usize
+    }
+
+    fn set(&mut self, column: usize, digit: usize) {
+        let (word, shift) = position(self.base, self.width, column);
+        self.words[word] |= (digit as u64) << shift;
+    }
+
+    /// The number these digits make, if it fits
+    pub fn value(&self) -> Option<u128> {
+        (0..self.width).try_fold(0u128, |value, column| {
+            value
+                .checked_mul(self.base as u128)?
+                .checked_add(self.get(column) as u128)
+        })
+    }
+
+    /// The number these digits make as 32-bit limbs, the lowest first
+    fn limbs(&self) -> Vec<u32> {
+        let mut limbs = Vec::new();
+        for column in 0..self.width {
+            let mut carry = self.get(column) as u64;
+            for limb in limbs.iter_mut() {
+                let value = *limb as u64 * self.base as u64 + carry;
+                *limb = value as u32;
+                carry = value >> 32;
+            }
+            if carry > 0 {
+                limbs.push(carry as u32);
+            }
+        }
+        limbs
+    }
+
+    /// The product of the numbers both make, in decimal however wide they are
+    pub fn product(&self, other: &Digits) -> String {
+        let (a, b) = (self.limbs(), other.limbs());
+        let mut limbs = vec![0u32; a.len() + b.len()];
+        for (i, &x) in a.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &y) in b.iter().enumerate() {
+                let value = x as u64 * y as u64 + limbs[i + j] as u64 + carry;
+                limbs[i + j] = value as u32;
+                carry = value >> 32;
+            }
+            limbs[i + b.len()] = carry as u32;
+        }
+        // split off nine decimal digits at a time, the lowest first
+        let mut chunks = Vec::new();
+        while limbs.iter().any(|&limb| limb != 0) {
+            let mut remainder = 0u64;
+            for limb in limbs.iter_mut().rev() {
+                let value = remainder << 32 | *limb as u64;
+                *limb = (value / 1_000_000_000) as u32;
+                remainder = value % 1_000_000_000;
+            }
+            chunks.push(remainder);
+        }
+        match chunks.split_last() {
+            None => "0".to_string(),
+            Some((highest, rest)) => {
+                let mut decimal = highest.to_string();
+                for chunk in rest.iter().rev() {
+                    decimal += &format!("{:09}", chunk);
+                }
+                decimal
+            }
+        }
+    }
+}
+
+impl Display for Digits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for column in 0..self.width {
+            let digit = char::from_digit(self.get(column) as u32, self.base).unwrap();
+            write!(f, "{}", digit)?;
+        }
+        Ok(())
+    }
+}
+
+/// All lines of a diagnostic report, equally wide
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostics {
+    pub base: u32,
+    pub width: usize,
+    stride: usize,
+    words: Vec<u64>,
+    /// How often every digit occurs in every column, `base` counts per column
+    counts: Vec<usize>,
+}
+
+impl Diagnostics {
+    /// Report from lines of digits of `base`, 2 up to 36
+    pub fn new(lines: &[&str], base: u32) -> Result<Self, String> {
+        if !(2..=36).contains(&base) {
+            return Err(format!("base {} isn't from 2 to 36", base));
+        }
+        let width = lines.first().map_or(0, |line| line.len());
+        if let Some(i) = lines.iter().position(|line| line.len() != width) {
+            return Err(format!(
+                "line {} is {} digits wide instead of {}",
+                i + 1,
+                lines[i].len(),
+                width
+            ));
+        }
+        let stride = stride(base, width);
+        let mut words = Vec::with_capacity(lines.len() * stride);
+        let mut counts = vec![0; width * base as usize];
+        for line in lines {
+            let start = words.len();
+            words.resize(start + stride, 0);
+            for (column, c) in line.bytes().enumerate() {
+                let digit = (c as char)
+                    .to_digit(base)
+                    .ok_or(format!("'{}' isn't a digit of base {}", c as char, base))?;
+                let (word, shift) = position(base, width, column);
+                words[start + word] |= (digit as u64) << shift;
+                counts[column * base as usize + digit as usize] += 1;
+            }
+        }
+        Ok(Self {
+            base,
+            width,
+            stride,
+            words,
+            counts,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len() / self.stride
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Digit in `column` of `line`
+    pub fn get(&self, line: usize, column: usize) -> usize {
+        let (word, shift) = position(self.base, self.width, column);
+        let mask = (1 << packing(self.base).0) - 1;
+        (self.words[line * self.stride + word] >> shift & mask) as usize
+    }
+
+    pub fn line(&self, line: usize) -> Digits {
+        let start = line * self.stride;
+        Digits {
+            base: self.base,
+            width: self.width,
+            words: self.words[start..start + self.stride].to_vec(),
+        }
+    }
+
+    /// How often every digit occurs in `column`
+    pub fn counts(&self, column: usize) -> &[usize] {
+        let base = self.base as usize;
+        &self.counts[column * base..(column + 1) * base]
+    }
+
+    /// The digit `criterion` picks in every column, out of all digits of the base
+    fn rate(&self, criterion: Criterion) -> Digits {
+        let mut rate = Digits::new(self.base, self.width);
+        for column in 0..self.width {
+            let digit = criterion.pick_including_absent(self.counts(column));
+            rate.set(column, digit.unwrap_or_default());
+        }
+        rate
+    }
+
+    /// Most common digit per column, the lowest on a tie
+    pub fn gamma(&self) -> Digits {
+        self.rate(Criterion::GAMMA)
+    }
+
+    /// Least common digit per column, the highest on a tie
+    ///
+    /// Digits absent from a column occur zero times, so they're the least common.
+    /// That keeps a binary epsilon the complement of gamma even in columns where
+    /// every line agrees, at the cost of picking digits no line has for bases above 2.
+    pub fn epsilon(&self) -> Digits {
+        self.rate(Criterion::EPSILON)
+    }
+
+    /// The lines as strings of digits
+    pub fn lines(&self) -> Vec<String> {
+        (0..self.len()).map(|i| self.line(i).to_string()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packing() {
+        let wide = format!("1{}01", "0".repeat(97));
+        let report = Diagnostics::new(&["0101", "1100", "0111"], 2).unwrap();
+        assert_eq!(report.len(), 3);
+        assert_eq!(report.line(1).value(), Some(12));
+        assert_eq!(report.gamma().to_string(), "0101");
+        assert_eq!(report.epsilon().to_string(), "1010");
+        assert_eq!(report.counts(1), &[0, 3]);
+        let report = Diagnostics::new(&[&wide, &wide], 2).unwrap();
+        assert_eq!(report.stride, 2);
+        assert_eq!(report.line(1).value(), Some(1 << 99 | 1));
+        assert_eq!(report.lines(), vec![wide.clone(), wide]);
+        assert_eq!(Digits::new(2, 130).value(), Some(0));
+        let mut ones = Digits::new(2, 130);
+        (0..130).for_each(|column| ones.set(column, 1));
+        assert_eq!(ones.value(), None);
+    }
+
+    #[test]
+    fn other_bases() {
+        let report = Diagnostics::new(&["012", "210", "112"], 3).unwrap();
+        assert_eq!(report.gamma().to_string(), "012");
+        assert_eq!(report.epsilon().to_string(), "221");
+        assert_eq!(report.gamma().value(), Some(5));
+        assert_eq!(report.epsilon().value(), Some(25));
+
+        let report = Diagnostics::new(&["ff", "0F", "f0"], 16).unwrap();
+        assert_eq!(report.gamma().value(), Some(0xff));
+        assert_eq!(report.epsilon().value(), Some(0xee));
+        assert_eq!(report.lines(), vec!["ff", "0f", "f0"]);
+
+        // 32 ternary digits to a word
+        let line = "0120".repeat(9);
+        let report = Diagnostics::new(&[&line], 3).unwrap();
+        assert_eq!(report.stride, 2);
+        assert_eq!(report.lines(), vec![line]);
+    }
+
+    #[test]
+    fn absent_digits() {
+        // every line has a 1 in the middle column, epsilon still picks the 0
+        let report = Diagnostics::new(&["010", "110", "011"], 2).unwrap();
+        assert_eq!(report.gamma().to_string(), "010");
+        assert_eq!(report.epsilon().to_string(), "101");
+        // no line has a 2 in the middle column or a 1 in the last, epsilon picks them anyway
+        let report = Diagnostics::new(&["012", "210", "112"], 3).unwrap();
+        assert_eq!(report.counts(1), &[0, 3, 0]);
+        assert_eq!(report.counts(2), &[1, 0, 2]);
+        assert_eq!(report.epsilon().to_string(), "221");
+        // an absent digit beats every present one, however rare
+        let report = Diagnostics::new(&["11", "21"], 3).unwrap();
+        assert_eq!(report.epsilon().to_string(), "02");
+    }
+
+    #[test]
+    fn products() {
+        let report = Diagnostics::new(&["0101", "1100", "0111"], 2).unwrap();
+        assert_eq!(report.line(0).product(&report.line(1)), "60");
+        assert_eq!(report.line(0).product(&Digits::new(2, 4)), "0");
+        let mut ones = Digits::new(2, 130);
+        (0..130).for_each(|column| ones.set(column, 1));
+        // (2^130 - 1)^2
+        assert_eq!(
+            ones.product(&ones),
+            "1852673427797059126777135760139006525649597495714881516923614347267155928547329"
+        );
+        let report = Diagnostics::new(&["z0"], 36).unwrap();
+        assert_eq!(
+            report.line(0).product(&report.line(0)),
+            (1260u64 * 1260).to_string()
+        );
+    }
+}
@@ -1,105 +1,52 @@
-use nom::{
-    character::complete::digit1, character::complete::newline, multi::separated_list1, IResult,
-};
+//! Day 3: Binary Diagnostic
 
-pub fn process_part1(input: &str) -> String {
-    let (_, numbers) = parse_input(input).unwrap();
-    let bit_size: usize = numbers.first().unwrap().len();
-    let mut bits = vec![0; bit_size];
-    let halflen = numbers.len() / 2;
-    for row in numbers.into_iter() {
-        for (i, c) in row.chars().enumerate() {
-            if c == '1' {
-                bits[i] += 1;
-            }
-        }
-    }
+use aoc_parse::lines_of;
+use nom::{bytes::complete::take_while1, combinator::map_res, IResult};
 
-    let mut gamma = 0;
-    let mut epsilon = 0;
-    for b in bits.into_iter() {
-        gamma <<= 1;
-        epsilon <<= 1;
-        if b > halflen {
-            gamma += 1;
-        } else {
-            epsilon += 1;
-        }
-    }
-    (gamma * epsilon).to_string()
-}
+pub mod diagnostics;
+pub mod trie;
 
-pub fn process_part2(input: &str) -> String {
-    let (_, numbers) = parse_input(input).unwrap();
-    let bit_size: usize = numbers.first().unwrap().len();
-    // let mut bits = vec![0; bit_size];
-    let mut oxygen = numbers.clone();
-    for i in 0..bit_size {
-        let mut oxygen_bits = vec![0; bit_size];
-        for row in oxygen.iter() {
-            for (j, c) in row.chars().enumerate() {
-                if c == '1' {
-                    oxygen_bits[j] += 1;
-                }
-            }
-        }
-        let len = oxygen.len() as f64 / 2.0;
-        if oxygen_bits[i] as f64 > len {
-            oxygen.retain(|x| &x[i..=i] == "1");
-        } else if oxygen_bits[i] as f64 == len {
-            oxygen.retain(|x| &x[i..=i] == "1");
-        } else {
-            oxygen.retain(|x| &x[i..=i] == "0");
-        }
-    }
+use diagnostics::Diagnostics;
+use trie::{Criterion, Trie};
 
-    let mut scrubber = numbers;
-    for i in 0..bit_size {
-        let mut scrubber_bits = vec![0; bit_size];
-        for row in scrubber.iter() {
-            for (j, c) in row.chars().enumerate() {
-                if c == '1' {
-                    scrubber_bits[j] += 1;
-                }
-            }
-        }
-        let len = scrubber.len() as f64 / 2.0;
-        if scrubber_bits[i] as f64 > len {
-            scrubber.retain(|x| &x[i..=i] == "0");
-        } else if scrubber_bits[i] as f64 == len {
-            scrubber.retain(|x| &x[i..=i] == "0")
-        } else {
-            scrubber.retain(|x| &x[i..=i] == "1");
-        }
-        if scrubber.len() == 1 {
-            break;
-        }
-    }
-    let answer = bin_to_usize(oxygen[0]) * bin_to_usize(scrubber[0]);
-    answer.to_string()
-}
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(3);
 
-fn bin_to_usize(b: &str) -> usize {
-    let mut result = 0;
-    for b in b.chars() {
-        result <<= 1;
-        if b == '1' {
-            result += 1;
-        }
-    }
-    result
+/// Product of the gamma and epsilon rates, see [`Diagnostics::epsilon`] for digits absent from a column
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
+pub fn process_part1(input: &str) -> String {
+    let (_, report) = parse_input(input).unwrap();
+    let gamma = report.gamma();
+    let epsilon = report.epsilon();
+    #[cfg(feature = "trace")]
+    tracing::debug!(%gamma, %epsilon, "rates");
+    gamma.product(&epsilon)
 }
 
-type Line<'a> = &'a str;
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
+pub fn process_part2(input: &str) -> String {
+    let (_, report) = parse_input(input).unwrap();
+    let trie = Trie::new(&report);
+    let oxygen = trie.rating(Criterion::OXYGEN).unwrap();
+    let co2 = trie.rating(Criterion::CO2).unwrap();
+    #[cfg(feature = "trace")]
+    tracing::debug!(%oxygen, %co2, "ratings");
+    oxygen.product(&co2)
+}
 
-fn parse_input(input: &str) -> IResult<&str, Vec<Line>> {
-    let (input, lines) = separated_list1(newline, parse_line)(input)?;
-    Ok((input, lines))
+/// Parses one diagnostic report of binary digits per line
+pub fn parse_input(input: &str) -> IResult<&str, Diagnostics> {
+    parse_report(2)(input)
 }
 
-fn parse_line(input: &str) -> IResult<&str, Line> {
-    let (input, line) = digit1(input)?;
-    Ok((input, line))
+/// Parses one diagnostic report of digits of `base` per line
+pub fn parse_report(base: u32) -> impl FnMut(&str) -> IResult<&str, Diagnostics> {
+    move |input| {
+        map_res(
+            lines_of(take_while1(|c: char| c.is_digit(base))),
+            |lines: Vec<&str>| Diagnostics::new(&lines, base),
+        )(input)
+    }
 }
 
 #[cfg(test)]
@@ -141,4 +88,37 @@ mod tests {
         let result = process_part2(input);
         assert_eq!(result, "230");
     }
+
+    #[test]
+    fn wide() {
+        let input = ["10".repeat(64), "10".repeat(64), "01".repeat(64)].join("\n");
+        let product =
+            "25731575386070265649682441113041757300575426651510819358340185509566576270450";
+        assert_eq!(process_part1(&input), product);
+        assert_eq!(process_part2(&input), product);
+
+        let input = ["1100", "1010", "0110", "0101", "1111"]
+            .map(|p| p.repeat(50))
+            .join("\n");
+        assert_eq!(
+            process_part1(&input),
+            "160673325747629867800812748480187405513848360442707240798351937765794788695591384666117552859418040081861415508529228750"
+        );
+        assert_eq!(
+            process_part2(&input),
+            "860749959362302863218639724001003958109901930943074504276885380888186368012096703568486890318310929009971868795692296875"
+        );
+    }
+
+    #[test]
+    fn ragged() {
+        assert!(parse_input("01\n011").is_err());
+        assert!(parse_report(3)("012\n21").is_err());
+        assert_eq!(
+            Diagnostics::new(&["01", "011"], 2),
+            Err("line 2 is 3 digits wide instead of 2".to_string())
+        );
+        assert!(Diagnostics::new(&["012"], 2).is_err());
+        assert!(Diagnostics::new(&["01"], 37).is_err());
+    }
 }
@@ -0,0 +1,292 @@
+//! Ratings found by walking a trie of the report, a binary one for binary reports.
+//!
+//! Every node counts the lines below it, so a rating only looks at one count
+//! per digit in every column. A node with a single line keeps that line instead of a chain of
+//! children, which keeps the trie small for long lines.
+
+use crate::diagnostics::{Diagnostics, Digits};
+
+/// Whether to keep the most or the least common digit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Select {
+    MostCommon,
+    LeastCommon,
+}
+
+/// Which digit to keep when several are as common
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tie {
+    Lowest,
+    Highest,
+}
+
+/// How a digit is picked out of a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Criterion {
+    pub select: Select,
+    pub tie: Tie,
+}
+
+impl Criterion {
+    pub const GAMMA: Criterion = Criterion {
+        select: Select::MostCommon,
+        tie: Tie::Lowest,
+    };
+    pub const EPSILON: Criterion = Criterion {
+        select: Select::LeastCommon,
+        tie: Tie::Highest,
+    };
+    pub const OXYGEN: Criterion = Criterion {
+        select: Select::MostCommon,
+        tie: Tie::Highest,
+    };
+    pub const CO2: Criterion = Criterion {
+        select: Select::LeastCommon,
+        tie: Tie::Lowest,
+    };
+
+    fn best<'a>(&self, digits: impl Iterator<Item = (usize, &'a usize)>) -> Option<usize> {
+        digits
+            .max_by(|(a, count_a), (b, count_b)| {
+                let by_count = match self.select {
+                    Select::MostCommon => count_a.cmp(count_b),
+                    Select::LeastCommon => count_b.cmp(count_a),
+                };
+                by_count.then(match self.tie {
+                    Tie::Lowest => b.cmp(a),
+                    Tie::Highest => a.cmp(b),
+                })
+            })
+            .map(|(digit, _)| digit)
+    }
+
+    /// Digit to keep given how often every digit occurs, only ever one that's there
+    pub fn pick(&self, counts: &[usize]) -> Option<usize> {
+        self.best(counts.iter().enumerate().filter(|(_, &count)| count > 0))
+    }
+
+    /// Digit to keep given how often every digit occurs, counting those that aren't there
+    pub fn pick_including_absent(&self, counts: &[usize]) -> Option<usize> {
+        self.best(counts.iter().enumerate())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Node {
+    /// Number of lines below this node
+    count: usize,
+    /// The line below this node, while there's only one
+    line: u32,
+}
+
+/// The lines of a report by their leading digits
+#[derive(Debug, Clone)]
+pub struct Trie<'a> {
+    report: &'a Diagnostics,
+    nodes: Vec<Node>,
+    /// Index of the node for every digit in the next column, `base` per node, 0 when there's none
+    children: Vec<u32>,
+}
+
+impl<'a> Trie<'a> {
+    pub fn new(report: &'a Diagnostics) -> Self {
+        let mut trie = Self {
+            report,
+            nodes: vec![Node::default()],
+            children: vec![0; report.base as usize],
+        };
+        for line in 0..report.len() {
+            trie.insert(line);
+        }
+        trie
+    }
+
+    fn children(&self, node: usize) -> &[u32] {
+        let base = self.report.base as usize;
+        &self.children[node * base..(node + 1) * base]
+    }
+
+    fn child(&mut self, node: usize, digit: usize) -> usize {
+        let slot = node * self.report.base as usize + digit;
+        if self.children[slot] != 0 {
+            return self.children[slot] as usize;
+        }
+        self.nodes.push(Node::default());
+        self.children
+            .extend(std::iter::repeat_n(0, self.report.base as usize));
+        let child = self.nodes.len() - 1;
+        self.children[slot] = child as u32;
+        child
+    }
+
+    fn insert(&mut self, line: usize) {
+        let mut node = 0;
+        for column in 0..=self.report.width {
+            self.nodes[node].count += 1;
+            if self.nodes[node].count == 1 {
+                self.nodes[node].line = line as u32;
+                return;
+            }
+            if column == self.report.width {
+                // a repeated line
+                return;
+            }
+            if self.nodes[node].count == 2 {
+                // the line that was here alone moves down a column
+                let other = self.nodes[node].line as usize;
+                let child = self.child(node, self.report.get(other, column));
+                self.nodes[child].count = 1;
+                self.nodes[child].line = other as u32;
+            }
+            node = self.child(node, self.report.get(line, column));
+        }
+    }
+
+    /// Number of lines below `node` for every digit in the next column
+    fn counts(&self, node: usize) -> Vec<usize> {
+        self.children(node)
+            .iter()
+            .map(|&child| match child {
+                0 => 0,
+                child => self.nodes[child as usize].count,
+            })
+            .collect()
+    }
+
+    /// The line left after keeping the digit `criterion` picks in every column
+    pub fn rating(&self, criterion: Criterion) -> Option<Digits> {
+        let mut node = 0;
+        for _column in 0..self.report.width {
+            if self.nodes[node].count <= 1 {
+                break;
+            }
+            let digit = criterion.pick(&self.counts(node))?;
+            node = self.children(node)[digit] as usize;
+            #[cfg(feature = "trace")]
+            tracing::trace!(
+                column = _column,
+                remaining = self.nodes[node].count,
+                "filter"
+            );
+        }
+        let found = self.nodes[node];
+        (found.count > 0).then(|| self.report.line(found.line as usize))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Filters on the strings themselves, the way the ratings are defined
+    fn naive(lines: &[String], base: u32, criterion: Criterion) -> String {
+        let mut remaining: Vec<&String> = lines.iter().collect();
+        let mut column = 0;
+        while remaining.len() > 1 && column < lines[0].len() {
+            let digit = |line: &String| line[column..=column].to_lowercase();
+            let mut counts = vec![0; base as usize];
+            for line in remaining.iter() {
+                counts[u32::from_str_radix(&digit(line), base).unwrap() as usize] += 1;
+            }
+            let present = (0..base as usize).filter(|&d| counts[d] > 0);
+            let target = match criterion.select {
+                Select::MostCommon => present.clone().map(|d| counts[d]).max(),
+                Select::LeastCommon => present.clone().map(|d| counts[d]).min(),
+            };
+            let mut tied = present.filter(|&d| Some(counts[d]) == target);
+            let keep = match criterion.tie {
+                Tie::Lowest => tied.next(),
+                Tie::Highest => tied.next_back(),
+            }
+            .unwrap();
+            let keep = char::from_digit(keep as u32, base).unwrap().to_string();
+            remaining.retain(|line| digit(line) == keep);
+            column += 1;
+        }
+        remaining[0].to_lowercase()
+    }
+
+    fn criteria() -> Vec<Criterion> {
+        [Select::MostCommon, Select::LeastCommon]
+            .into_iter()
+            .flat_map(|select| [Tie::Lowest, Tie::Highest].map(|tie| Criterion { select, tie }))
+            .collect()
+    }
+
+    fn check(lines: &[String], base: u32) {
+        let strs: Vec<&str> = lines.iter().map(|line| line.as_str()).collect();
+        let report = Diagnostics::new(&strs, base).unwrap();
+        let trie = Trie::new(&report);
+        for criterion in criteria() {
+            let rating = trie.rating(criterion).unwrap().to_string();
+            assert_eq!(rating, naive(lines, base, criterion), "{:?}", criterion);
+        }
+    }
+
+    fn random_lines(count: usize, width: usize, base: u32) -> Vec<String> {
+        let mut state = 0x2545f4914f6cdd1d_u64;
+        let mut random = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        (0..count)
+            .map(|_| {
+                (0..width)
+                    .map(|_| char::from_digit((random() % base as u64) as u32, base).unwrap())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn criteria_example() {
+        let lines: Vec<String> =
+            "00100 11110 10110 10111 10101 01111 00111 11100 10000 11001 00010 01010"
+                .split(' ')
+                .map(String::from)
+                .collect();
+        check(&lines, 2);
+        let strs: Vec<&str> = lines.iter().map(|line| line.as_str()).collect();
+        let report = Diagnostics::new(&strs, 2).unwrap();
+        let trie = Trie::new(&report);
+        assert_eq!(trie.rating(Criterion::OXYGEN).unwrap().to_string(), "10111");
+        assert_eq!(trie.rating(Criterion::CO2).unwrap().to_string(), "01010");
+        let ties_low = Criterion {
+            select: Select::MostCommon,
+            tie: Tie::Lowest,
+        };
+        assert_eq!(trie.rating(ties_low).unwrap().to_string(), "10110");
+    }
+
+    #[test]
+    fn repeated_lines() {
+        let lines: Vec<String> = ["0110", "0110", "0111", "1000", "0110"]
+            .map(String::from)
+            .to_vec();
+        check(&lines, 2);
+        let report = Diagnostics::new(&[], 2).unwrap();
+        assert_eq!(Trie::new(&report).rating(Criterion::OXYGEN), None);
+    }
+
+    #[test]
+    fn wide_ratings() {
+        check(&random_lines(2000, 128, 2), 2);
+    }
+
+    #[test]
+    fn other_bases() {
+        check(&random_lines(500, 40, 3), 3);
+        check(&random_lines(500, 20, 16), 16);
+        let lines: Vec<String> = ["210", "201", "120", "212", "0A2"]
+            .map(String::from)
+            .to_vec();
+        check(&lines, 11);
+        let strs: Vec<&str> = lines.iter().map(|line| line.as_str()).collect();
+        let report = Diagnostics::new(&strs, 11).unwrap();
+        let trie = Trie::new(&report);
+        assert_eq!(trie.rating(Criterion::OXYGEN).unwrap().to_string(), "212");
+        assert_eq!(trie.rating(Criterion::CO2).unwrap().to_string(), "0a2");
+    }
+}
@@ -1,11 +1,17 @@
+//! Day 4: Giant Squid
+
+use aoc_parse::{blank_line_separated, comma_list, lines_of, unsigned};
 use nom::{
-    character::complete::char as nomchar,
-    character::complete::{digit1, newline, space0, space1},
+    character::complete::{newline, space0, space1},
     multi::separated_list1,
     sequence::{pair, preceded, terminated},
     IResult,
 };
 
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(4);
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part1(input: &str) -> String {
     let (input, (draws, mut boards)) = parse_input(input).unwrap();
     assert_eq!(input, "");
@@ -27,6 +33,8 @@ pub fn process_part1(input: &str) -> String {
                     .filter(|&x| x != 100)
                     .sum::<usize>()
                     * draw;
+                #[cfg(feature = "trace")]
+                tracing::debug!(draw, score, "bingo");
                 return score.to_string();
             }
             new_boards.push(new_board);
@@ -36,6 +44,7 @@ pub fn process_part1(input: &str) -> String {
     "".to_string()
 }
 
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part2(input: &str) -> String {
     let (_, (draws, mut boards)) = parse_input(input).unwrap();
     for &draw in draws.iter() {
@@ -56,6 +65,8 @@ pub fn process_part2(input: &str) -> String {
                     .filter(|&x| x != 100)
                     .sum::<usize>()
                     * draw;
+                #[cfg(feature = "trace")]
+                tracing::debug!(draw, score, boards_left = boards.len() - 1, "bingo");
                 if boards.len() == 1 {
                     return score.to_string();
                 }
@@ -68,7 +79,8 @@ pub fn process_part2(input: &str) -> String {
     "".to_string()
 }
 
-fn bingo(b: &[Vec<usize>]) -> bool {
+/// Whether a board with its drawn numbers replaced by 100 has a full row or column
+pub fn bingo(b: &[Vec<usize>]) -> bool {
     let row_bingo: Vec<usize> = vec![100, 100, 100, 100, 100];
     let mut column_bingo = [0; 5];
     for row in b.iter() {
@@ -90,29 +102,20 @@ fn bingo(b: &[Vec<usize>]) -> bool {
     false
 }
 
-type Draws = Vec<usize>;
-type Boards = Vec<Vec<Vec<usize>>>;
+/// Numbers in the order they're drawn
+pub type Draws = Vec<usize>;
+/// Bingo boards, each a list of rows
+pub type Boards = Vec<Vec<Vec<usize>>>;
 
-fn parse_input(input: &str) -> IResult<&str, (Draws, Boards)> {
-    let (input, draws) = terminated(
-        separated_list1(nomchar(','), parse_usize),
-        pair(newline, newline),
-    )(input)?;
-    let (input, boards) = separated_list1(pair(newline, newline), parse_board)(input)?;
+/// Parses the draws and the 5x5 boards, row by row
+pub fn parse_input(input: &str) -> IResult<&str, (Draws, Boards)> {
+    let (input, draws) = terminated(comma_list(unsigned), pair(newline, newline))(input)?;
+    let (input, boards) = blank_line_separated(parse_board)(input)?;
     Ok((input, (draws, boards)))
 }
 
 fn parse_board(input: &str) -> IResult<&str, Vec<Vec<usize>>> {
-    let (input, board) = separated_list1(
-        newline,
-        preceded(space0, separated_list1(space1, parse_usize)),
-    )(input)?;
-    Ok((input, board))
-}
-
-fn parse_usize(input: &str) -> IResult<&str, usize> {
-    let (input, n) = digit1(input)?;
-    Ok((input, n.parse().unwrap()))
+    lines_of(preceded(space0, separated_list1(space1, unsigned)))(input)
 }
 
 #[cfg(test)]
@@ -1,14 +1,15 @@
+//! Day 5: Hydrothermal Venture
+
 use std::cmp::Ordering;
 use std::collections::HashMap;
 
-use nom::{
-    bytes::complete::tag,
-    character::complete::newline,
-    character::complete::{char as nomchar, digit1},
-    multi::separated_list1,
-    IResult,
-};
+use aoc_parse::{lines_of, unsigned};
+use nom::{bytes::complete::tag, character::complete::char as nomchar, IResult};
+
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(5);
 
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part1(input: &str) -> String {
     let (_, lines) = parse_input(input).unwrap();
     let mut field = HashMap::new();
@@ -19,10 +20,13 @@ pub fn process_part1(input: &str) -> String {
         .for_each(|point| {
             *field.entry(point).or_insert(0_usize) += 1;
         });
+    #[cfg(feature = "trace")]
+    tracing::debug!(points = field.len(), "vents drawn");
     let crossings = field.into_values().filter(|&x| x > 1).count();
     crossings.to_string()
 }
 
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part2(input: &str) -> String {
     let (_, lines) = parse_input(input).unwrap();
     let mut field = HashMap::new();
@@ -32,20 +36,26 @@ pub fn process_part2(input: &str) -> String {
         .for_each(|point| {
             *field.entry(point).or_insert(0_usize) += 1;
         });
+    #[cfg(feature = "trace")]
+    tracing::debug!(points = field.len(), "vents drawn");
     let crossings = field.into_values().filter(|&x| x > 1).count();
     crossings.to_string()
 }
 
+/// Position on the ocean floor
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Point {
-    x: usize,
-    y: usize,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
 }
 
+/// Line of hydrothermal vents, including both end points
 #[derive(Debug, Clone)]
-struct Line {
-    start: Point,
-    end: Point,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Line {
+    pub start: Point,
+    pub end: Point,
 }
 
 impl Line {
@@ -104,9 +114,9 @@ impl Iterator for LineIter {
     }
 }
 
-fn parse_input(input: &str) -> IResult<&str, Vec<Line>> {
-    let (input, lines) = separated_list1(newline, parse_line)(input)?;
-    Ok((input, lines))
+/// Parses one `x1,y1 -> x2,y2` line per line
+pub fn parse_input(input: &str) -> IResult<&str, Vec<Line>> {
+    lines_of(parse_line)(input)
 }
 
 fn parse_line(input: &str) -> IResult<&str, Line> {
@@ -117,17 +127,12 @@ fn parse_line(input: &str) -> IResult<&str, Line> {
 }
 
 fn parse_point(input: &str) -> IResult<&str, Point> {
-    let (input, x) = parse_usize(input)?;
+    let (input, x) = unsigned(input)?;
     let (input, _) = nomchar(',')(input)?;
-    let (input, y) = parse_usize(input)?;
+    let (input, y) = unsigned(input)?;
     Ok((input, Point { x, y }))
 }
 
-fn parse_usize(input: &str) -> IResult<&str, usize> {
-    let (input, digits) = digit1(input)?;
-    Ok((input, digits.parse().unwrap()))
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
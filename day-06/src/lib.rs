@@ -1,16 +1,15 @@
-use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::{alpha1, newline, one_of, space0, space1},
-    character::complete::{char as nomchar, digit1},
-    combinator::recognize,
-    multi::{many1, separated_list1},
-    IResult,
-};
+//! Day 6: Lanternfish
 
+use aoc_parse::{comma_list, unsigned};
+use nom::IResult;
+
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(6);
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part1(input: &str) -> String {
     let (_, mut fishes) = parse_input(input).unwrap();
-    for _ in 0..80 {
+    for _day in 0..80 {
         let mut new_fishes = Vec::new();
         for fish in fishes.iter_mut() {
             if fish.timer == 0 {
@@ -19,11 +18,14 @@ pub fn process_part1(input: &str) -> String {
             fish.age();
         }
         fishes.extend(new_fishes.into_iter());
+        #[cfg(feature = "trace")]
+        tracing::trace!(day = _day + 1, fish = fishes.len());
     }
 
     fishes.len().to_string()
 }
 
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part2(input: &str) -> String {
     let (_, fishes) = parse_input(input).unwrap();
     let mut total_fish = fishes.len();
@@ -39,21 +41,28 @@ pub fn process_part2(input: &str) -> String {
         total_fish += spawn_now;
         spawn_on_day[weekday] += spawn_on_day7;
         (spawn_on_day7, spawn_on_day8) = (spawn_on_day8, spawn_now);
+        #[cfg(feature = "trace")]
+        tracing::trace!(day = day + 1, fish = total_fish);
     }
     total_fish.to_string()
 }
 
-#[derive(Debug)]
-struct Fish {
-    timer: usize,
+/// A single lanternfish
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fish {
+    /// Days until it spawns a new fish
+    pub timer: usize,
 }
 
 impl Fish {
-    fn new() -> Self {
+    /// A newly spawned fish
+    pub fn new() -> Self {
         Fish { timer: 8 }
     }
 
-    fn age(&mut self) {
+    /// Advances the fish by a day, resetting the timer to 6 after it spawns
+    pub fn age(&mut self) {
         if self.timer == 0 {
             self.timer = 6;
         } else {
@@ -62,19 +71,20 @@ impl Fish {
     }
 }
 
-fn parse_input(input: &str) -> IResult<&str, Vec<Fish>> {
-    let (input, lines) = separated_list1(nomchar(','), parse_fish)(input)?;
-    Ok((input, lines))
+impl Default for Fish {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-fn parse_fish(input: &str) -> IResult<&str, Fish> {
-    let (input, timer) = parse_usize(input)?;
-    Ok((input, Fish { timer }))
+/// Parses the comma-separated timers of the initial fish
+pub fn parse_input(input: &str) -> IResult<&str, Vec<Fish>> {
+    comma_list(parse_fish)(input)
 }
 
-fn parse_usize(input: &str) -> IResult<&str, usize> {
-    let (input, digits) = digit1(input)?;
-    Ok((input, digits.parse().unwrap()))
+fn parse_fish(input: &str) -> IResult<&str, Fish> {
+    let (input, timer) = unsigned(input)?;
+    Ok((input, Fish { timer }))
 }
 
 #[cfg(test)]
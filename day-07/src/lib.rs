@@ -1,15 +1,22 @@
-use nom::{
-    character::complete::char as nomchar, character::complete::digit1, multi::separated_list1,
-    IResult,
-};
+//! Day 7: The Treachery of Whales
 
+use aoc_parse::{comma_list, unsigned};
+use nom::IResult;
+
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(7);
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part1(input: &str) -> String {
     let (_, mut crabs) = parse_input(input).unwrap();
     let med = median(&mut crabs);
+    #[cfg(feature = "trace")]
+    tracing::debug!(position = med, "median");
     let fuel_cost: i32 = crabs.iter().map(|&x| (x - med).abs()).sum();
     fuel_cost.to_string()
 }
 
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part2(input: &str) -> String {
     let (_, crabs) = parse_input(input).unwrap();
     let mut previous_cost = i32::MAX;
@@ -22,6 +29,8 @@ pub fn process_part2(input: &str) -> String {
             })
             .sum();
         if fuel_cost > previous_cost {
+            #[cfg(feature = "trace")]
+            tracing::debug!(position = mn - 1, "cheapest");
             break;
         }
         previous_cost = fuel_cost;
@@ -44,14 +53,9 @@ fn median(numbers: &mut Vec<i32>) -> i32 {
     }
 }
 
-fn parse_input(input: &str) -> IResult<&str, Vec<i32>> {
-    let (input, lines) = separated_list1(nomchar(','), parse_i32)(input)?;
-    Ok((input, lines))
-}
-
-fn parse_i32(input: &str) -> IResult<&str, i32> {
-    let (input, digits) = digit1(input)?;
-    Ok((input, digits.parse().unwrap()))
+/// Parses the comma-separated horizontal crab positions
+pub fn parse_input(input: &str) -> IResult<&str, Vec<i32>> {
+    comma_list(unsigned)(input)
 }
 
 #[cfg(test)]
@@ -1,3 +1,5 @@
+//! Day 8: Seven Segment Search
+
 use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
@@ -11,10 +13,15 @@ use nom::{
     IResult,
 };
 
-const DISPLAY_NUMBERS: [&str; 10] = [
+/// Segments lit for each digit 0 to 9 on a correctly wired display
+pub const DISPLAY_NUMBERS: [&str; 10] = [
     "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
 ];
 
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(8);
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part1(input: &str) -> String {
     let (_, displays) = parse_input(input).unwrap();
     let count: usize = displays
@@ -29,22 +36,29 @@ pub fn process_part1(input: &str) -> String {
     count.to_string()
 }
 
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part2(input: &str) -> String {
     let (_, mut displays) = parse_input(input).unwrap();
     let output_sum: usize = displays.iter_mut().map(|x| x.output_value()).sum();
     output_sum.to_string()
 }
 
+/// Seven-segment display with scrambled wiring
 #[derive(Debug)]
-struct Display {
-    patterns: Vec<String>,
-    output: Vec<String>,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Display {
+    /// The ten unique signal patterns, each with sorted segments
+    pub patterns: Vec<String>,
+    /// The four output digits as shown
+    pub output: Vec<String>,
     // segment_mapping: Option<HashMap<char, char>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     _output_value: Option<usize>,
 }
 
 impl Display {
-    fn output_value(&mut self) -> usize {
+    /// Decoded four-digit output, deduced from the patterns on first use
+    pub fn output_value(&mut self) -> usize {
         if self._output_value.is_none() {
             self.deduce();
         }
@@ -187,11 +201,14 @@ impl Display {
             tens *= 10;
         }
 
+        #[cfg(feature = "trace")]
+        tracing::trace!(output = ?self.output, value, "decoded");
         self._output_value = Some(value);
     }
 }
 
-fn parse_input(input: &str) -> IResult<&str, Vec<Display>> {
+/// Parses one display per line, `patterns | output`
+pub fn parse_input(input: &str) -> IResult<&str, Vec<Display>> {
     let (input, lines) = separated_list1(newline, parse_display)(input)?;
     Ok((input, lines))
 }
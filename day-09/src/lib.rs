@@ -1,11 +1,14 @@
+//! Day 9: Smoke Basin
+
 use std::collections::VecDeque;
 
-use nom::{
-    character::complete::{digit1, newline},
-    multi::separated_list1,
-    IResult,
-};
+use aoc_parse::lines_of;
+use nom::{character::complete::digit1, IResult};
+
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(9);
 
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part1(input: &str) -> String {
     let (_, input) = parse_input(input).unwrap();
     let mut height_map: Vec<Vec<u8>> = vec![vec![99; input[0].len() + 2]];
@@ -42,9 +45,12 @@ pub fn process_part1(input: &str) -> String {
             }
         }
     }
+    #[cfg(feature = "trace")]
+    tracing::debug!(low_points = low_points.len(), "low points");
     risk_level.to_string()
 }
 
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part2(input: &str) -> String {
     let (_, input) = parse_input(input).unwrap();
     let mut height_map: Vec<Vec<u8>> = vec![vec![99; input[0].len() + 2]];
@@ -110,6 +116,8 @@ pub fn process_part2(input: &str) -> String {
                 }
             }
         }
+        #[cfg(feature = "trace")]
+        tracing::trace!(x = start_x, y = start_y, size = basin_count, "basin");
         basin_sizes.push(basin_count);
     }
 
@@ -119,14 +127,9 @@ pub fn process_part2(input: &str) -> String {
     answer.to_string()
 }
 
-fn parse_input(input: &str) -> IResult<&str, Vec<&str>> {
-    let (input, lines) = separated_list1(newline, parse_line)(input)?;
-    Ok((input, lines))
-}
-
-fn parse_line(input: &str) -> IResult<&str, &str> {
-    let (input, line) = digit1(input)?;
-    Ok((input, line))
+/// Parses the height map into its rows of digits
+pub fn parse_input(input: &str) -> IResult<&str, Vec<&str>> {
+    lines_of(digit1)(input)
 }
 
 #[cfg(test)]
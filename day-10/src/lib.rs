@@ -1,9 +1,15 @@
+//! Day 10: Syntax Scoring
+
 use nom::{
     character::complete::{newline, one_of},
     multi::{many1, separated_list1},
     IResult,
 };
 
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(10);
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part1(input: &str) -> String {
     let (_, chars) = parse_input(input).unwrap();
     let mut syntax_error_score = 0;
@@ -46,11 +52,12 @@ pub fn process_part1(input: &str) -> String {
             }
         }
         if corrupted {
+            #[cfg(feature = "trace")]
+            tracing::trace!(syntax_error_score, "corrupted line");
             continue;
         }
         let mut autocomplete_score = 0_usize;
-        while !stack.is_empty() {
-            let c = stack.pop().unwrap();
+        while let Some(c) = stack.pop() {
             autocomplete_score *= 5;
             match c {
                 '(' => autocomplete_score += 1,
@@ -66,6 +73,7 @@ pub fn process_part1(input: &str) -> String {
     syntax_error_score.to_string()
 }
 
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part2(input: &str) -> String {
     let (_, chars) = parse_input(input).unwrap();
     let mut autocomplete_scores = Vec::new();
@@ -103,11 +111,12 @@ pub fn process_part2(input: &str) -> String {
             }
         }
         if corrupted {
+            #[cfg(feature = "trace")]
+            tracing::trace!("corrupted line");
             continue;
         }
         let mut autocomplete_score = 0_usize;
-        while !stack.is_empty() {
-            let c = stack.pop().unwrap();
+        while let Some(c) = stack.pop() {
             autocomplete_score *= 5;
             match c {
                 '(' => autocomplete_score += 1,
@@ -117,15 +126,19 @@ pub fn process_part2(input: &str) -> String {
                 _ => panic!("Didn't expect character '{}'", c),
             }
         }
+        #[cfg(feature = "trace")]
+        tracing::trace!(autocomplete_score, "incomplete line");
         autocomplete_scores.push(autocomplete_score);
     }
     autocomplete_scores.sort();
     autocomplete_scores[autocomplete_scores.len() / 2].to_string()
 }
 
-type Line = Vec<char>;
+/// A line of navigation subsystem brackets
+pub type Line = Vec<char>;
 
-fn parse_input(input: &str) -> IResult<&str, Vec<Line>> {
+/// Parses one line of brackets per line
+pub fn parse_input(input: &str) -> IResult<&str, Vec<Line>> {
     let (input, lines) = separated_list1(newline, parse_line)(input)?;
     Ok((input, lines))
 }
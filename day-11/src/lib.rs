@@ -1,145 +1,159 @@
-use std::collections::VecDeque;
+//! Day 11: Dumbo Octopus
 
-use nom::{
-    character::complete::{digit1, newline},
-    multi::separated_list1,
-    IResult,
-};
+use std::{collections::VecDeque, fmt::Display};
 
+use aoc_parse::lines_of;
+use nom::{character::complete::digit1, IResult};
+
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(11);
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part1(input: &str) -> String {
-    let (_, input_strs) = parse_input(input).unwrap();
-    let mut octopuses = input_strs
-        .iter()
-        .map(|x| {
-            x.chars()
-                .map(|y| y.to_digit(10).expect("Need a valid digit!") as i8)
-                .collect::<Vec<i8>>()
-        })
-        .collect::<Vec<Vec<i8>>>();
-
-    let mut flashes = 0;
-    let mut hundred_steps_flashes = 0;
-    for step in 1..300 {
-        let mut deque = VecDeque::new();
+    let (_, mut octopuses) = parse_input(input).unwrap();
+    let flashes: usize = (0..100).map(|_| octopuses.step()).sum();
+    flashes.to_string()
+}
 
-        // energize
-        (0..10).for_each(|y| {
-            (0..10).for_each(|x| {
-                octopuses[y][x] += 1;
-                if octopuses[y][x] > 9 {
-                    deque.push_back((x, y));
-                }
-            });
-        });
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
+pub fn process_part2(input: &str) -> String {
+    let (_, mut octopuses) = parse_input(input).unwrap();
+    match octopuses.synchronize(MAX_STEPS) {
+        Some(step) => step.to_string(),
+        None => format!("never synchronizes within {} steps", MAX_STEPS),
+    }
+}
 
-        // flash
-        let mut flashed = 0;
-        while !deque.is_empty() {
-            let (x, y) = deque.pop_front().unwrap();
-            octopuses[y][x] = -10;
-            flashed += 1;
-            flashes += 1;
-            for new_y in range(&y) {
-                for new_x in range(&x) {
-                    octopuses[new_y][new_x] += 1;
-                    if octopuses[new_y][new_x] > 9 && !deque.contains(&(new_x, new_y)) {
-                        deque.push_back((new_x, new_y));
-                    }
-                }
-            }
-        }
+/// Steps to give up after for octopuses that never all flash at once
+pub const MAX_STEPS: usize = 10_000;
+
+/// Grid of octopus energy levels
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Octopuses {
+    energy: Vec<Vec<i8>>,
+    /// Number of steps taken so far
+    pub steps: usize,
+    /// Total number of flashes so far
+    pub flashes: usize,
+}
 
-        // reset
-        (0..10).for_each(|y| {
-            (0..10).for_each(|x| {
-                if octopuses[y][x] < 0 {
-                    octopuses[y][x] = 0;
-                }
-            });
-        });
-        if flashed == 100 {
-            break;
+impl Octopuses {
+    pub fn new(rows: &[&str]) -> Self {
+        let energy = rows
+            .iter()
+            .map(|x| {
+                x.chars()
+                    .map(|y| y.to_digit(10).expect("Need a valid digit!") as i8)
+                    .collect::<Vec<i8>>()
+            })
+            .collect::<Vec<Vec<i8>>>();
+        Self {
+            energy,
+            steps: 0,
+            flashes: 0,
         }
-        if step == 100 {
-            hundred_steps_flashes = flashes;
+    }
+
+    /// Number of octopuses in the grid
+    pub fn len(&self) -> usize {
+        self.height() * self.width()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn width(&self) -> usize {
+        self.energy.first().map_or(0, |row| row.len())
+    }
+
+    pub fn height(&self) -> usize {
+        self.energy.len()
+    }
+
+    /// Steps until all octopuses flash at once, returns that step unless it takes
+    /// more than `limit` steps in total
+    pub fn synchronize(&mut self, limit: usize) -> Option<usize> {
+        let count = self.len();
+        while self.steps < limit {
+            if self.step() == count {
+                return Some(self.steps);
+            }
         }
+        None
     }
-    hundred_steps_flashes.to_string()
-}
 
-pub fn process_part2(input: &str) -> String {
-    let (_, input_strs) = parse_input(input).unwrap();
-    let mut octopuses = input_strs
-        .iter()
-        .map(|x| {
-            x.chars()
-                .map(|y| y.to_digit(10).expect("Need a valid digit!") as i8)
-                .collect::<Vec<i8>>()
-        })
-        .collect::<Vec<Vec<i8>>>();
-
-    let mut all_together = 0;
-    for step in 1..300 {
+    /// Performs a single step, returns the number of octopuses that flashed
+    pub fn step(&mut self) -> usize {
         let mut deque = VecDeque::new();
 
         // energize
-        (0..10).for_each(|y| {
-            (0..10).for_each(|x| {
-                octopuses[y][x] += 1;
-                if octopuses[y][x] > 9 {
+        self.energy.iter_mut().enumerate().for_each(|(y, row)| {
+            row.iter_mut().enumerate().for_each(|(x, octopus)| {
+                *octopus += 1;
+                if *octopus > 9 {
                     deque.push_back((x, y));
                 }
             });
         });
 
-        // flash
+        // flash, one wave of octopuses at a time
         let mut flashed = 0;
         while !deque.is_empty() {
-            let (x, y) = deque.pop_front().unwrap();
-            octopuses[y][x] = -10;
-            flashed += 1;
-            for new_y in range(&y) {
-                for new_x in range(&x) {
-                    octopuses[new_y][new_x] += 1;
-                    if octopuses[new_y][new_x] > 9 && !deque.contains(&(new_x, new_y)) {
-                        deque.push_back((new_x, new_y));
+            #[cfg(feature = "trace")]
+            tracing::trace!(step = self.steps + 1, flashing = deque.len(), "flash wave");
+            for _ in 0..deque.len() {
+                let (x, y) = deque.pop_front().unwrap();
+                self.energy[y][x] = -10;
+                flashed += 1;
+                for new_y in range(y, self.height()) {
+                    for new_x in range(x, self.width()) {
+                        self.energy[new_y][new_x] += 1;
+                        if self.energy[new_y][new_x] > 9 && !deque.contains(&(new_x, new_y)) {
+                            deque.push_back((new_x, new_y));
+                        }
                     }
                 }
             }
         }
 
         // reset
-        (0..10).for_each(|y| {
-            (0..10).for_each(|x| {
-                if octopuses[y][x] < 0 {
-                    octopuses[y][x] = 0;
-                }
-            });
-        });
-        if flashed == 100 {
-            all_together = step;
-            break;
-        }
+        self.energy
+            .iter_mut()
+            .flat_map(|row| row.iter_mut())
+            .filter(|octopus| **octopus < 0)
+            .for_each(|octopus| *octopus = 0);
+
+        self.steps += 1;
+        self.flashes += flashed;
+        #[cfg(feature = "trace")]
+        tracing::debug!(step = self.steps, flashed, "step");
+        flashed
     }
-    all_together.to_string()
 }
 
-fn range(i: &usize) -> core::ops::Range<usize> {
-    match i {
-        0 => 0..2,
-        9 => 8..10,
-        _ => (i - 1)..(i + 2),
+impl Display for Octopuses {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.energy.iter() {
+            for octopus in row.iter() {
+                write!(f, "{}", octopus)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
     }
 }
 
-fn parse_input(input: &str) -> IResult<&str, Vec<&str>> {
-    let (input, lines) = separated_list1(newline, parse_line)(input)?;
-    Ok((input, lines))
+/// Indices of `i` and its neighbours in a dimension of length `len`
+fn range(i: usize, len: usize) -> core::ops::Range<usize> {
+    i.saturating_sub(1)..(i + 2).min(len)
 }
 
-fn parse_line(input: &str) -> IResult<&str, &str> {
-    let (input, line) = digit1(input)?;
-    Ok((input, line))
+/// Parses the grid of energy levels, one row of digits per line
+pub fn parse_input(input: &str) -> IResult<&str, Octopuses> {
+    let (input, lines) = lines_of(digit1)(input)?;
+    Ok((input, Octopuses::new(&lines)))
 }
 
 #[cfg(test)]
@@ -176,5 +190,50 @@ mod tests {
 5283751526";
         let result = process_part2(input);
         assert_eq!(result, "195");
+        let (_, mut octopuses) = parse_input(input).unwrap();
+        assert_eq!(octopuses.synchronize(100), None);
+        assert_eq!(octopuses.steps, 100);
+        assert_eq!(octopuses.synchronize(MAX_STEPS), Some(195));
+    }
+
+    #[test]
+    fn steps() {
+        let (_, mut octopuses) = parse_input(
+            "11111
+19991
+19191
+19991
+11111",
+        )
+        .unwrap();
+        assert_eq!(octopuses.step(), 9);
+        assert_eq!(octopuses.to_string(), "34543\n40004\n50005\n40004\n34543\n");
+        assert_eq!(octopuses.step(), 0);
+        assert_eq!(octopuses.to_string(), "45654\n51115\n61116\n51115\n45654\n");
+        assert_eq!((octopuses.steps, octopuses.flashes), (2, 9));
+    }
+
+    #[test]
+    fn steps_snapshot() {
+        let input = "5483143223
+2745854711
+5264556173
+6141336146
+6357385478
+4167524645
+2176841721
+6882881134
+4846848554
+5283751526";
+        let (_, mut octopuses) = parse_input(input).unwrap();
+        let mut steps = format!("before any steps\n{}", octopuses);
+        for _ in 0..10 {
+            let flashed = octopuses.step();
+            steps.push_str(&format!(
+                "\nafter step {}, {} flashed, {} in total\n{}",
+                octopuses.steps, flashed, octopuses.flashes, octopuses
+            ));
+        }
+        insta::assert_snapshot!(steps);
     }
 }
@@ -1,4 +1,6 @@
-use std::collections::HashMap;
+//! Day 12: Passage Pathing
+
+use std::{collections::HashMap, fmt::Display};
 
 use itertools::Itertools;
 
@@ -10,12 +12,17 @@ use nom::{
     IResult,
 };
 
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(12);
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part1(input: &str) -> String {
     let (_, graph) = parse_input(input).unwrap();
     let count = graph.count_paths("start", "end");
     count.to_string()
 }
 
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part2(input: &str) -> String {
     let (_, mut graph) = parse_input(input).unwrap();
     graph.cleanse();
@@ -23,29 +30,48 @@ pub fn process_part2(input: &str) -> String {
     count.to_string()
 }
 
-struct Graph<'a> {
+/// Undirected cave system, caves with uppercase names may be visited any number of times
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Graph<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
     adjacency_list: HashMap<&'a str, Vec<&'a str>>,
 }
 
 impl<'a> Graph<'a> {
-    fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             adjacency_list: HashMap::new(),
         }
     }
 
-    fn add_edge(&mut self, u: &'a str, v: &'a str) {
-        self.adjacency_list
-            .entry(u)
-            .or_insert_with(Vec::new)
-            .push(v);
+    pub fn add_edge(&mut self, u: &'a str, v: &'a str) {
+        self.adjacency_list.entry(u).or_default().push(v);
+        self.adjacency_list.entry(v).or_default().push(u);
+    }
+
+    /// Returns all caves in no particular order
+    pub fn caves(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.adjacency_list.keys().copied()
+    }
+
+    /// Returns the name of `cave` as stored in the graph, if it exists
+    pub fn cave(&self, cave: &str) -> Option<&'a str> {
         self.adjacency_list
-            .entry(v)
-            .or_insert_with(Vec::new)
-            .push(u);
+            .get_key_value(cave)
+            .map(|(&name, _)| name)
+    }
+
+    /// Returns the neighbours of `cave`, if it exists
+    pub fn neighbours(&self, cave: &str) -> Option<&[&'a str]> {
+        self.adjacency_list.get(cave).map(|v| v.as_slice())
     }
 
-    fn count_paths(&self, start: &'a str, end: &'a str) -> usize {
+    pub fn count_paths(&self, start: &'a str, end: &'a str) -> usize {
+        self.paths(start, end).len()
+    }
+
+    /// Returns all paths from start to end that visit small caves at most once
+    pub fn paths(&self, start: &'a str, end: &'a str) -> Vec<Vec<&'a str>> {
         assert!(self.adjacency_list.contains_key(start));
         assert!(self.adjacency_list.contains_key(end));
 
@@ -55,6 +81,8 @@ impl<'a> Graph<'a> {
 
         while let Some((current, path)) = stack.pop() {
             if current == end {
+                #[cfg(feature = "trace")]
+                tracing::trace!(path = %path.join(","), "path");
                 paths.push(path);
                 continue;
             }
@@ -69,10 +97,10 @@ impl<'a> Graph<'a> {
                 }
             }
         }
-        paths.len()
+        paths
     }
 
-    fn count_paths2(&self, start: &'a str, end: &'a str) -> usize {
+    pub fn count_paths2(&self, start: &'a str, end: &'a str) -> usize {
         assert!(self.adjacency_list.contains_key(start));
         assert!(self.adjacency_list.contains_key(end));
 
@@ -82,6 +110,8 @@ impl<'a> Graph<'a> {
 
         while let Some((current, path)) = stack.pop() {
             if current == end {
+                #[cfg(feature = "trace")]
+                tracing::trace!(path = %path.join(","), "path");
                 paths.push(path);
                 continue;
             }
@@ -105,16 +135,34 @@ impl<'a> Graph<'a> {
         paths.len()
     }
 
-    fn cleanse(&mut self) {
+    /// Removes all edges leading back into the start cave
+    pub fn cleanse(&mut self) {
         self.adjacency_list
             .values_mut()
             .for_each(|v| v.retain(|&s| s != "start"))
     }
 }
 
+impl Default for Graph<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for Graph<'_> {
+    /// Writes the adjacency list, one cave per line, sorted by name
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (cave, neighbours) in self.adjacency_list.iter().sorted() {
+            writeln!(f, "{}: {}", cave, neighbours.iter().sorted().join(", "))?;
+        }
+        Ok(())
+    }
+}
+
 type Line<'a> = (&'a str, &'a str);
 
-fn parse_input(input: &str) -> IResult<&str, Graph> {
+/// Parses one `a-b` tunnel per line into a graph
+pub fn parse_input(input: &str) -> IResult<&str, Graph<'_>> {
     let (input, lines) = separated_list1(newline, parse_line)(input)?;
     let mut graph = Graph::new();
     lines.into_iter().for_each(|(u, v)| {
@@ -123,7 +171,7 @@ fn parse_input(input: &str) -> IResult<&str, Graph> {
     Ok((input, graph))
 }
 
-fn parse_line(input: &str) -> IResult<&str, Line> {
+fn parse_line(input: &str) -> IResult<&str, Line<'_>> {
     let (input, (start, _, end)) = tuple((alpha1, nomchar('-'), alpha1))(input)?;
     Ok((input, (start, end)))
 }
@@ -237,4 +285,22 @@ start-RW";
         let result = process_part2(input);
         assert_eq!(result, "3509");
     }
+
+    #[test]
+    fn paths() {
+        let (_, graph) = parse_input(
+            "start-A
+A-b
+A-end
+b-end",
+        )
+        .unwrap();
+        assert_eq!(
+            graph.to_string(),
+            "A: b, end, start\nb: A, end\nend: A, b\nstart: A\n"
+        );
+        let paths = graph.paths("start", "end");
+        assert_eq!(paths.len(), 3);
+        assert!(paths.contains(&vec!["start", "A", "b", "A", "end"]));
+    }
 }
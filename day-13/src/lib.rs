@@ -1,12 +1,19 @@
+//! Day 13: Transparent Origami
+
+use std::fmt::Display;
+
+use aoc_parse::{lines_of, unsigned};
 use nom::{
     bytes::complete::tag,
-    character::complete::{char as nomchar, u64 as nomu64},
-    character::complete::{newline, one_of},
-    multi::separated_list1,
+    character::complete::{char as nomchar, newline, one_of},
     sequence::{pair, terminated, tuple},
     IResult,
 };
 
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(13);
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part1(input: &str) -> String {
     let (_, (points, folds)) = parse_input(input).unwrap();
     let mut paper = Paper::new(points);
@@ -14,6 +21,7 @@ pub fn process_part1(input: &str) -> String {
     paper.count().to_string()
 }
 
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part2(input: &str) -> String {
     let (_, (points, folds)) = parse_input(input).unwrap();
     let mut paper = Paper::new(points);
@@ -32,25 +40,31 @@ pub fn process_part2(input: &str) -> String {
         .collect::<String>()
 }
 
+/// Position of a dot, x to the right and y down
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct Point {
-    x: usize,
-    y: usize,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
 }
 
+/// Fold line, folding the bottom half up (Y) or the right half left (X)
 #[derive(Debug)]
-enum Fold {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Fold {
     X(usize),
     Y(usize),
 }
 
+/// Transparent paper with dots, indexed by row then column
 #[derive(Debug)]
-struct Paper {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Paper {
     dots: Vec<Vec<bool>>,
 }
 
 impl Paper {
-    fn new(points: Vec<Point>) -> Self {
+    pub fn new(points: Vec<Point>) -> Self {
         let (xmax, ymax) = points
             .iter()
             .fold((0, 0), |(x, y), el| (x.max(el.x), y.max(el.y)));
@@ -61,7 +75,7 @@ impl Paper {
         Self { dots }
     }
 
-    fn fold(&mut self, fold: &Fold) {
+    pub fn fold(&mut self, fold: &Fold) {
         match fold {
             Fold::X(x) => {
                 (1..=((*x).min(self.width() - x))).for_each(|i| {
@@ -84,9 +98,12 @@ impl Paper {
                 self.dots.pop().unwrap();
             }
         }
+        #[cfg(feature = "trace")]
+        tracing::debug!(%fold, dots = self.count(), width = self.width(), height = self.height());
     }
 
-    fn count(&self) -> usize {
+    /// Number of visible dots
+    pub fn count(&self) -> usize {
         self.dots
             .iter()
             .flat_map(|row| row.iter())
@@ -94,32 +111,55 @@ impl Paper {
             .count()
     }
 
-    fn width(&self) -> usize {
+    pub fn width(&self) -> usize {
         self.dots.first().unwrap().len()
     }
+
+    pub fn height(&self) -> usize {
+        self.dots.len()
+    }
+}
+
+impl Display for Fold {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::X(x) => write!(f, "fold along x={}", x),
+            Self::Y(y) => write!(f, "fold along y={}", y),
+        }
+    }
 }
 
-fn parse_input(input: &str) -> IResult<&str, (Vec<Point>, Vec<Fold>)> {
-    let (input, points) = terminated(
-        separated_list1(newline, parse_point),
-        pair(newline, newline),
-    )(input)?;
-    let (input, folds) = separated_list1(newline, parse_fold)(input)?;
+impl Display for Paper {
+    /// Draws dots as '#' and empty spots as '.'
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.dots.iter() {
+            for &dot in row.iter() {
+                write!(f, "{}", if dot { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses the dots, a blank line and the fold instructions
+pub fn parse_input(input: &str) -> IResult<&str, (Vec<Point>, Vec<Fold>)> {
+    let (input, points) = terminated(lines_of(parse_point), pair(newline, newline))(input)?;
+    let (input, folds) = lines_of(parse_fold)(input)?;
     Ok((input, (points, folds)))
 }
 
 fn parse_point(input: &str) -> IResult<&str, Point> {
-    let (input, (x, _, y)) = tuple((nomu64, nomchar(','), nomu64))(input)?;
-    let (x, y) = (x as usize, y as usize);
+    let (input, (x, _, y)) = tuple((unsigned, nomchar(','), unsigned))(input)?;
     Ok((input, Point { x, y }))
 }
 
 fn parse_fold(input: &str) -> IResult<&str, Fold> {
     let (input, (_, dir, _, i)) =
-        tuple((tag("fold along "), one_of("xy"), nomchar('='), nomu64))(input)?;
+        tuple((tag("fold along "), one_of("xy"), nomchar('='), unsigned))(input)?;
     let fold = match dir {
-        'x' => Fold::X(i as usize),
-        'y' => Fold::Y(i as usize),
+        'x' => Fold::X(i),
+        'y' => Fold::Y(i),
         _ => unreachable!(),
     };
     Ok((input, fold))
@@ -209,4 +249,81 @@ fold along x=5";
         let count = result.chars().filter(|&c| c == '#').count();
         assert_eq!(count, 16);
     }
+
+    #[test]
+    fn display() {
+        let (_, (points, folds)) = parse_input(
+            "0,0
+4,0
+1,2
+3,2
+
+fold along x=2",
+        )
+        .unwrap();
+        let mut paper = Paper::new(points);
+        assert_eq!(paper.to_string(), "#...#\n.....\n.#.#.\n");
+        assert_eq!(folds[0].to_string(), "fold along x=2");
+        paper.fold(&folds[0]);
+        assert_eq!(paper.to_string(), "#.\n..\n.#\n");
+    }
+
+    #[test]
+    fn folds_snapshot() {
+        let input = "6,10
+0,14
+9,10
+0,3
+10,4
+4,11
+6,0
+6,12
+4,1
+0,13
+10,12
+3,4
+3,0
+8,4
+1,10
+2,14
+8,10
+9,0
+
+fold along y=7
+fold along x=5";
+        let (_, (points, folds)) = parse_input(input).unwrap();
+        let mut paper = Paper::new(points);
+        let mut steps = format!("{} dots\n{}", paper.count(), paper);
+        for fold in folds {
+            paper.fold(&fold);
+            steps.push_str(&format!("\n{}, {} dots\n{}", fold, paper.count(), paper));
+        }
+        insta::assert_snapshot!(steps);
+    }
+
+    #[test]
+    fn part2_snapshot() {
+        let input = "6,10
+0,14
+9,10
+0,3
+10,4
+4,11
+6,0
+6,12
+4,1
+0,13
+10,12
+3,4
+3,0
+8,4
+1,10
+2,14
+8,10
+9,0
+
+fold along y=7
+fold along x=5";
+        insta::assert_snapshot!(process_part2(input));
+    }
 }
@@ -1,3 +1,5 @@
+//! Day 14: Extended Polymerization
+
 use std::collections::HashMap;
 
 use nom::{
@@ -9,19 +11,25 @@ use nom::{
     IResult,
 };
 
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(14);
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part1(input: &str) -> String {
     let (r, (molecule, inserts)) = parse_input(input).unwrap();
     assert!(r.is_empty());
     compute(molecule, inserts, 10)
 }
 
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part2(input: &str) -> String {
     let (r, (molecule, inserts)) = parse_input(input).unwrap();
     assert!(r.is_empty());
     compute(molecule, inserts, 40)
 }
 
-fn compute(molecule: Molecule, inserts: Inserts, iterations: usize) -> String {
+/// Difference between the most and least common element after `iterations` steps
+pub fn compute(molecule: Molecule, inserts: Inserts, iterations: usize) -> String {
     let mut map1: HashMap<(char, char), u128> =
         inserts.keys().map(|&combo| (combo, 0_u128)).collect();
     molecule.windows(2).for_each(|w| {
@@ -30,7 +38,7 @@ fn compute(molecule: Molecule, inserts: Inserts, iterations: usize) -> String {
     });
     let mut map2 = map1.clone();
 
-    (0..iterations).for_each(|_i| {
+    (0..iterations).for_each(|_step| {
         map2 = map1.clone();
         map1.iter().for_each(|(&(c1, c2), &v)| {
             if let Some(&insert) = inserts.get(&(c1, c2)) {
@@ -40,6 +48,12 @@ fn compute(molecule: Molecule, inserts: Inserts, iterations: usize) -> String {
             }
         });
         std::mem::swap(&mut map1, &mut map2);
+        #[cfg(feature = "trace")]
+        tracing::trace!(
+            step = _step + 1,
+            length = map1.values().sum::<u128>() + 1,
+            "insertion step"
+        );
     });
     let mut counts = HashMap::new();
     map1.into_iter().for_each(|((c1, _), v)| {
@@ -53,10 +67,13 @@ fn compute(molecule: Molecule, inserts: Inserts, iterations: usize) -> String {
     (max - min).to_string()
 }
 
-type Molecule = Vec<char>;
-type Inserts = HashMap<(char, char), char>;
+/// Polymer template, one element per char
+pub type Molecule = Vec<char>;
+/// Pair insertion rules, `AB -> C` maps `('A', 'B')` to `'C'`
+pub type Inserts = HashMap<(char, char), char>;
 
-fn parse_input(input: &str) -> IResult<&str, (Molecule, Inserts)> {
+/// Parses the polymer template and the pair insertion rules
+pub fn parse_input(input: &str) -> IResult<&str, (Molecule, Inserts)> {
     let (input, molecule) = terminated(alpha1, pair(newline, newline))(input)?;
     let molecule: Vec<_> = molecule.chars().collect();
     let (input, inserts) = separated_list1(newline, parse_insert)(input)?;
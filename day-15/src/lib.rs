@@ -1,9 +1,14 @@
+//! Day 15: Chiton
+
 use std::collections::{BinaryHeap, HashMap};
 
-use nom::{
-    character::complete::digit1, character::complete::newline, multi::separated_list1, IResult,
-};
+use aoc_parse::digit_grid;
+use nom::IResult;
+
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(15);
 
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part1(input: &str) -> String {
     let (r, cave) = parse_input(input).unwrap();
     assert!(r.is_empty());
@@ -12,6 +17,7 @@ pub fn process_part1(input: &str) -> String {
         .to_string()
 }
 
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part2(input: &str) -> String {
     let (r, mut cave) = parse_input(input).unwrap();
     assert!(r.is_empty());
@@ -41,6 +47,8 @@ fn find_path(start: (usize, usize), end: (usize, usize), cave: &Vec<Vec<u32>>) -
     }) = heap.pop()
     {
         if location == end {
+            #[cfg(feature = "trace")]
+            tracing::debug!(risk = distance, steps = path.len() - 1, "path found");
             return Some(distance);
         }
         if distance > distances[&location] {
@@ -155,17 +163,9 @@ impl std::cmp::Ord for Node {
 
 type Line = Vec<u32>;
 
-fn parse_input(input: &str) -> IResult<&str, Vec<Line>> {
-    let (input, lines) = separated_list1(newline, parse_line)(input)?;
-    Ok((input, lines))
-}
-
-fn parse_line(input: &str) -> IResult<&str, Line> {
-    let (input, line) = digit1(input)?;
-    Ok((
-        input,
-        line.chars().map(|c| c.to_digit(10).unwrap()).collect(),
-    ))
+/// Parses the risk levels, row by row
+pub fn parse_input(input: &str) -> IResult<&str, Vec<Line>> {
+    digit_grid(input)
 }
 
 #[cfg(test)]
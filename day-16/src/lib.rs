@@ -1,3 +1,7 @@
+//! Day 16: Packet Decoder
+
+use std::fmt::Display;
+
 use nom::{
     character::complete::char as nomchar,
     character::complete::one_of,
@@ -6,19 +10,25 @@ use nom::{
     IResult,
 };
 
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(16);
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part1(input: &str) -> String {
     let (_, bits) = parse_input(input).unwrap();
     let (_, packet) = parse_input_bits(&bits).unwrap();
     packet.version_sum().to_string()
 }
 
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part2(input: &str) -> String {
     let (_, bits) = parse_input(input).unwrap();
     let (_, packet) = parse_input_bits(&bits).unwrap();
     packet.expression().to_string()
 }
 
-fn parse_input(input: &str) -> IResult<&str, String> {
+/// Parses the hexadecimal transmission into a string of '0' and '1' bits
+pub fn parse_input(input: &str) -> IResult<&str, String> {
     let (input, hex_chars) = many1(one_of("0123456789ABCDEF"))(input)?;
     let mut bits = String::with_capacity(hex_chars.len() * 4);
     hex_chars.into_iter().for_each(|c| {
@@ -46,7 +56,8 @@ fn parse_input(input: &str) -> IResult<&str, String> {
     Ok((input, bits))
 }
 
-fn parse_input_bits(input: &str) -> IResult<&str, Packet> {
+/// Parses the outermost packet from a string of bits
+pub fn parse_input_bits(input: &str) -> IResult<&str, Packet> {
     let (input, packet) = parse_packet(input)?;
 
     Ok((input, packet))
@@ -65,30 +76,32 @@ fn parse_binary_number(input: &str) -> IResult<&str, u32> {
     Ok((input, n))
 }
 
+#[cfg_attr(
+    feature = "trace",
+    tracing::instrument(level = "trace", skip_all, fields(bits_left = input.len()))
+)]
 fn parse_packet(input: &str) -> IResult<&str, Packet> {
     let (input, version) = parse_version(input)?;
     let (input, id) = parse_id(input)?;
-    // dbg!(id);
     match id {
         4 => {
             // literal
-            // dbg!("literal", input);
             let (input, value) = parse_literal(input)?;
-            // dbg!(input, value);
+            #[cfg(feature = "trace")]
+            tracing::trace!(version, value, bits_left = input.len(), "literal");
             Ok((input, Packet::Literal(version, value)))
         }
         _ => {
             // operator
             let (input, length_type) = one_of("01")(input)?;
-            // dbg!(length_type);
             match length_type {
                 '0' => {
                     let (input, bit_length) = parse_bits(input, 15)?;
                     let (_, bit_length) = parse_binary_number(bit_length)?;
-                    // dbg!(bit_length);
                     let (input, packet_bits) = parse_bits(input, bit_length as usize)?;
-                    // dbg!(packet_bits);
                     let (_, packets) = many1(parse_packet)(packet_bits)?;
+                    #[cfg(feature = "trace")]
+                    tracing::trace!(version, id, bit_length, bits_left = input.len(), "operator");
                     let packet = Packet::Operator(version, id, packets);
                     Ok((input, packet))
                 }
@@ -96,9 +109,16 @@ fn parse_packet(input: &str) -> IResult<&str, Packet> {
                     let (input, sub_packet_count) = parse_bits(input, 11)?;
                     let (_, sub_packet_count) = parse_binary_number(sub_packet_count)?;
                     let sub_packet_count = sub_packet_count as usize;
-                    // dbg!(sub_packet_count);
                     let (input, packets) =
                         many_m_n(sub_packet_count, sub_packet_count, parse_packet)(input)?;
+                    #[cfg(feature = "trace")]
+                    tracing::trace!(
+                        version,
+                        id,
+                        sub_packet_count,
+                        bits_left = input.len(),
+                        "operator"
+                    );
                     let packet = Packet::Operator(version, id, packets);
                     Ok((input, packet))
                 }
@@ -141,7 +161,8 @@ fn parse_literal(input: &str) -> IResult<&str, u128> {
 }
 
 #[derive(Debug, PartialEq, Eq)]
-enum Packet {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Packet {
     // version, value
     Literal(u32, u128),
     // version, id, sub-packets
@@ -149,14 +170,16 @@ enum Packet {
 }
 
 impl Packet {
-    fn version_sum(&self) -> u32 {
+    /// Sum of the versions of this packet and all its sub-packets
+    pub fn version_sum(&self) -> u32 {
         match self {
             Self::Literal(v, _) => *v,
             Self::Operator(v, _, subs) => *v + subs.iter().map(|p| p.version_sum()).sum::<u32>(),
         }
     }
 
-    fn expression(&self) -> u128 {
+    /// Evaluates the expression the packet represents
+    pub fn expression(&self) -> u128 {
         match self {
             Self::Literal(_, value) => *value,
             Self::Operator(_, id, packets) => {
@@ -200,6 +223,45 @@ impl Packet {
     }
 }
 
+impl Packet {
+    /// Name of the operation an operator packet performs
+    pub fn operation(id: u32) -> &'static str {
+        match id {
+            0 => "sum",
+            1 => "product",
+            2 => "minimum",
+            3 => "maximum",
+            4 => "literal",
+            5 => "greater than",
+            6 => "less than",
+            7 => "equal to",
+            _ => "unknown",
+        }
+    }
+
+    fn fmt_indented(&self, f: &mut std::fmt::Formatter<'_>, depth: usize) -> std::fmt::Result {
+        let indent = "  ".repeat(depth);
+        match self {
+            Self::Literal(version, value) => {
+                writeln!(f, "{}literal v{}: {}", indent, version, value)
+            }
+            Self::Operator(version, id, packets) => {
+                writeln!(f, "{}{} v{}", indent, Self::operation(*id), version)?;
+                packets
+                    .iter()
+                    .try_for_each(|packet| packet.fmt_indented(f, depth + 1))
+            }
+        }
+    }
+}
+
+impl Display for Packet {
+    /// Writes the packet tree, one packet per line, sub-packets indented
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -351,4 +413,15 @@ mod tests {
         dbg!(&packet);
         assert_eq!(packet.expression(), 1);
     }
+
+    #[test]
+    fn display() {
+        let input = "38006F45291200";
+        let (_, bits) = parse_input(input).unwrap();
+        let (_, packet) = parse_input_bits(&bits).unwrap();
+        assert_eq!(
+            packet.to_string(),
+            "less than v1\n  literal v6: 10\n  literal v2: 20\n"
+        );
+    }
 }
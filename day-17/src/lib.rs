@@ -1,19 +1,26 @@
+//! Day 17: Trick Shot
+
 use std::ops::RangeInclusive;
 
-use nom::{
-    bytes::complete::tag, character::complete::i64 as nomi64, sequence::terminated, IResult,
-};
+use aoc_parse::signed;
+use nom::{bytes::complete::tag, combinator::map_res, sequence::terminated, IResult};
+
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(17);
 
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part1(input: &str) -> String {
     let (_, target_area) = parse_input(input).unwrap();
     let launch_point = Point { x: 0, y: 0 };
     let mut highest = 0;
-    for initial_x_vel in 1..=target_area.right {
-        for initial_y_vel in target_area.bottom..100 {
+    for initial_x_vel in 1..=target_area.right() {
+        for initial_y_vel in target_area.bottom()..100 {
             let probe = Probe::new(launch_point, initial_x_vel, initial_y_vel);
             if probe.launch(&target_area) {
                 let high = (initial_y_vel * (initial_y_vel + 1)) / 2;
                 if high > highest {
+                    #[cfg(feature = "trace")]
+                    tracing::trace!(x_vel = initial_x_vel, y_vel = initial_y_vel, high, "higher");
                     highest = high;
                 }
             }
@@ -22,14 +29,17 @@ pub fn process_part1(input: &str) -> String {
     highest.to_string()
 }
 
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part2(input: &str) -> String {
     let (_, target_area) = parse_input(input).unwrap();
     let launch_point = Point { x: 0, y: 0 };
     let mut successes = Vec::new();
-    for initial_x_vel in 1..=target_area.right {
-        for initial_y_vel in target_area.bottom..200 {
+    for initial_x_vel in 1..=target_area.right() {
+        for initial_y_vel in target_area.bottom()..200 {
             let probe = Probe::new(launch_point, initial_x_vel, initial_y_vel);
             if probe.launch(&target_area) {
+                #[cfg(feature = "trace")]
+                tracing::trace!(x_vel = initial_x_vel, y_vel = initial_y_vel, "hit");
                 successes.push((initial_x_vel, initial_y_vel));
             }
         }
@@ -37,83 +47,101 @@ pub fn process_part2(input: &str) -> String {
     successes.len().to_string()
 }
 
-fn parse_input(input: &str) -> IResult<&str, Area> {
+/// Parses `target area: x=A..B, y=C..D`
+pub fn parse_input(input: &str) -> IResult<&str, Area> {
     let (input, _) = tag("target area: x=")(input)?;
-    let (input, startx) = terminated(nomi64, tag(".."))(input)?;
-    let (input, endx) = terminated(nomi64, tag(", y="))(input)?;
-    let (input, starty) = terminated(nomi64, tag(".."))(input)?;
-    let (input, endy) = nomi64(input)?;
-    let area = Area::new(startx..=endx, starty..=endy);
-
-    Ok((input, area))
+    let (input, startx) = terminated(signed, tag(".."))(input)?;
+    let (input, endx) = terminated(signed, tag(", y="))(input)?;
+    let (input, starty) = terminated(signed, tag(".."))(input)?;
+    map_res(signed, move |endy| Area::new(startx..=endx, starty..=endy))(input)
 }
 
+/// Position with y pointing up
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Point {
-    x: i64,
-    y: i64,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
 }
 
+/// Target area the probe has to end up in after some step
 #[derive(Debug)]
-struct Area {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Area {
     x_range: RangeInclusive<i64>,
     y_range: RangeInclusive<i64>,
-    bottom: i64,
-    left: i64,
-    right: i64,
 }
 
 impl Area {
-    fn new(x_range: RangeInclusive<i64>, y_range: RangeInclusive<i64>) -> Self {
-        Self {
-            bottom: y_range.clone().min().unwrap(),
-            left: x_range.clone().min().unwrap(),
-            right: x_range.clone().max().unwrap(),
-            x_range,
-            y_range,
+    /// Area spanning both ranges, which can't be empty
+    pub fn new(x_range: RangeInclusive<i64>, y_range: RangeInclusive<i64>) -> Result<Self, String> {
+        if x_range.is_empty() || y_range.is_empty() {
+            return Err(format!("area x={:?}, y={:?} is empty", x_range, y_range));
         }
+        Ok(Self { x_range, y_range })
+    }
+
+    pub fn bottom(&self) -> i64 {
+        *self.y_range.start()
+    }
+
+    pub fn top(&self) -> i64 {
+        *self.y_range.end()
     }
 
-    fn contains(&self, point: Point) -> bool {
+    pub fn left(&self) -> i64 {
+        *self.x_range.start()
+    }
+
+    pub fn right(&self) -> i64 {
+        *self.x_range.end()
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
         self.x_range.contains(&point.x) && self.y_range.contains(&point.y)
     }
 }
 
-#[derive(Clone)]
-struct Probe {
-    x: i64,
-    y: i64,
-    x_vel: i64,
-    y_vel: i64,
+/// Probe position and velocity
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Probe {
+    pub x: i64,
+    pub y: i64,
+    pub x_vel: i64,
+    pub y_vel: i64,
 }
 
 impl Probe {
-    fn new(launch_point: Point, x_vel: i64, y_vel: i64) -> Self {
+    pub fn new(launch_point: Point, x_vel: i64, y_vel: i64) -> Self {
         let Point { x, y } = launch_point;
         Self { x, y, x_vel, y_vel }
     }
 
-    fn step(&mut self) {
+    /// Moves the probe, then applies drag and gravity
+    pub fn step(&mut self) {
         self.x += self.x_vel;
         self.y += self.y_vel;
         self.x_vel -= self.x_vel.signum();
         self.y_vel -= 1;
     }
 
-    fn chance(&self, area: &Area) -> bool {
-        !(self.y < area.bottom && self.y_vel.is_negative()
-            || self.x < area.left && !self.x_vel.is_positive()
-            || self.x > area.right && !self.x_vel.is_negative())
+    /// Whether the probe can still reach `area`
+    pub fn chance(&self, area: &Area) -> bool {
+        !(self.y < area.bottom() && self.y_vel.is_negative()
+            || self.x < area.left() && !self.x_vel.is_positive()
+            || self.x > area.right() && !self.x_vel.is_negative())
     }
 
-    fn location(&self) -> Point {
+    pub fn location(&self) -> Point {
         Point {
             x: self.x,
             y: self.y,
         }
     }
 
-    fn launch(mut self, target: &Area) -> bool {
+    /// Steps the probe until it's inside `target` (true) or can no longer get there (false)
+    pub fn launch(mut self, target: &Area) -> bool {
         loop {
             if !self.chance(target) {
                 break false;
@@ -143,4 +171,12 @@ mod tests {
         let result = process_part2(input);
         assert_eq!(result, "112");
     }
+
+    #[test]
+    fn area() {
+        let (_, area) = parse_input("target area: x=20..30, y=-10..-5").unwrap();
+        assert_eq!((area.left(), area.right()), (20, 30));
+        assert_eq!((area.bottom(), area.top()), (-10, -5));
+        assert!(parse_input("target area: x=20..30, y=-5..-10").is_err());
+    }
 }
@@ -1,3 +1,5 @@
+//! Day 18: Snailfish
+
 use std::{cell::RefCell, collections::VecDeque, fmt::Display, rc::Rc};
 
 use itertools::Itertools;
@@ -9,6 +11,10 @@ use nom::{
     IResult,
 };
 
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(18);
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part1(input: &str) -> String {
     let (rem, nums) = parse_input(input).unwrap();
     assert!(rem.is_empty());
@@ -17,6 +23,7 @@ pub fn process_part1(input: &str) -> String {
     s
 }
 
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part2(input: &str) -> String {
     let (rem, nums) = parse_input(input).unwrap();
     assert!(rem.is_empty());
@@ -40,18 +47,27 @@ pub fn process_part2(input: &str) -> String {
 }
 
 /// Sums all Nums one by one, reducing at every step
-fn sum_nums(nums: Vec<Rc<RefCell<Num>>>) -> Rc<RefCell<Num>> {
+pub fn sum_nums(nums: Vec<Rc<RefCell<Num>>>) -> Rc<RefCell<Num>> {
     let mut it = nums.into_iter();
     let first = it.next().unwrap();
     it.fold(first, |acc, el| {
-        let current = Rc::new(RefCell::new(Num::Pair(acc, el, None)));
-        current.borrow_mut().connect(None, current.clone());
-        reduce(current)
+        let sum = reduce(add(acc, el));
+        #[cfg(feature = "trace")]
+        tracing::debug!(sum = %sum.borrow(), "added");
+        sum
     })
 }
 
+/// Adds two Nums by pairing them up, without reducing the result
+pub fn add(left: Rc<RefCell<Num>>, right: Rc<RefCell<Num>>) -> Rc<RefCell<Num>> {
+    let current = Rc::new(RefCell::new(Num::Pair(left, right, None)));
+    current.borrow_mut().connect(None, current.clone());
+    current
+}
+
+/// Snailfish number, a binary tree with parent links
 #[derive(Clone, PartialEq, Eq)]
-enum Num {
+pub enum Num {
     /// (left, right, parent)
     Pair(Rc<RefCell<Num>>, Rc<RefCell<Num>>, Option<Rc<RefCell<Num>>>),
     /// (value, parent)
@@ -131,7 +147,7 @@ impl Num {
             Self::Single(val, parent) => {
                 if val >= 10 {
                     let new_left_val = val / 2;
-                    let new_right_val = (val + 1) / 2;
+                    let new_right_val = val.div_ceil(2);
                     let new_self = Self::Pair(
                         Rc::new(RefCell::new(Num::Single(new_left_val, None))),
                         Rc::new(RefCell::new(Num::Single(new_right_val, None))),
@@ -145,7 +161,7 @@ impl Num {
     }
 
     /// Returns the calculated magnitude of the Num
-    fn magnitude(&self) -> usize {
+    pub fn magnitude(&self) -> usize {
         match self {
             Self::Single(n, _) => *n as usize,
             Self::Pair(left, right, _) => {
@@ -166,8 +182,71 @@ impl Display for Num {
     }
 }
 
-/// Recursively set parents of all child nodes from node
-fn connect(node: Rc<RefCell<Num>>) {
+/// Serialized as nested two-element sequences, the same shape as the puzzle notation
+#[cfg(feature = "serde")]
+impl serde::Serialize for Num {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeTuple;
+        match self {
+            Self::Single(n, _) => serializer.serialize_u8(*n),
+            Self::Pair(left, right, _) => {
+                let mut pair = serializer.serialize_tuple(2)?;
+                pair.serialize_element(&*left.borrow())?;
+                pair.serialize_element(&*right.borrow())?;
+                pair.end()
+            }
+        }
+    }
+}
+
+/// Deserialized without parent links, use [`connect`] on the root before reducing
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Num {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct NumVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for NumVisitor {
+            type Value = Num;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a regular number or a pair")
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, n: u64) -> Result<Num, E> {
+                let n = u8::try_from(n).map_err(|_| E::custom("regular number too large"))?;
+                Ok(Num::Single(n, None))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, n: i64) -> Result<Num, E> {
+                let n = u64::try_from(n).map_err(|_| E::custom("negative regular number"))?;
+                self.visit_u64(n)
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Num, A::Error> {
+                use serde::de::Error;
+                let left: Num = seq
+                    .next_element()?
+                    .ok_or(A::Error::invalid_length(0, &self))?;
+                let right: Num = seq
+                    .next_element()?
+                    .ok_or(A::Error::invalid_length(1, &self))?;
+                if seq.next_element::<serde::de::IgnoredAny>()?.is_some() {
+                    return Err(A::Error::invalid_length(3, &self));
+                }
+                Ok(Num::Pair(
+                    Rc::new(RefCell::new(left)),
+                    Rc::new(RefCell::new(right)),
+                    None,
+                ))
+            }
+        }
+
+        deserializer.deserialize_any(NumVisitor)
+    }
+}
+
+/// Recursively set parents of all child nodes from node, needed for trees that weren't parsed
+pub fn connect(node: Rc<RefCell<Num>>) {
     let parent_clone = node.borrow().clone();
     match parent_clone {
         Num::Pair(left, right, _) => {
@@ -182,8 +261,20 @@ fn connect(node: Rc<RefCell<Num>>) {
 
 /// Explode all numbers in the tree that should explode, in left-dfs order
 fn explode(tree: Rc<RefCell<Num>>) {
-    while let Some(node) = should_explode(tree.clone()) {
-        explode_node(node.clone());
+    while explode_once(tree.clone()) {}
+}
+
+/// Explode the leftmost number that should explode, returns false if there was none
+pub fn explode_once(tree: Rc<RefCell<Num>>) -> bool {
+    if let Some(node) = should_explode(tree.clone()) {
+        #[cfg(feature = "trace")]
+        let pair = node.borrow().to_string();
+        explode_node(node);
+        #[cfg(feature = "trace")]
+        tracing::trace!(%pair, number = %tree.borrow(), "explode");
+        true
+    } else {
+        false
     }
 }
 
@@ -330,11 +421,18 @@ fn num_to_right(node: Rc<RefCell<Num>>) -> Option<Rc<RefCell<Num>>> {
     None
 }
 
-/// Find the leftmost number that should be split and split it
-fn split(tree: Rc<RefCell<Num>>) {
-    if let Some(node) = should_split(tree) {
+/// Find the leftmost number that should be split and split it, returns false if there was none
+pub fn split(tree: Rc<RefCell<Num>>) -> bool {
+    if let Some(node) = should_split(tree.clone()) {
+        #[cfg(feature = "trace")]
+        let value = node.borrow().value();
         node.borrow_mut().split();
         connect(node);
+        #[cfg(feature = "trace")]
+        tracing::trace!(value, number = %tree.borrow(), "split");
+        true
+    } else {
+        false
     }
 }
 
@@ -359,7 +457,7 @@ fn should_split(tree: Rc<RefCell<Num>>) -> Option<Rc<RefCell<Num>>> {
 }
 
 /// Reduces number (explodes and splits until fully reduced)
-fn reduce(tree: Rc<RefCell<Num>>) -> Rc<RefCell<Num>> {
+pub fn reduce(tree: Rc<RefCell<Num>>) -> Rc<RefCell<Num>> {
     let mut tree_string = tree.borrow().to_string();
     loop {
         explode(tree.clone());
@@ -372,14 +470,17 @@ fn reduce(tree: Rc<RefCell<Num>>) -> Rc<RefCell<Num>> {
     }
 }
 
-type Line = Rc<RefCell<Num>>;
+/// Root of a snailfish number tree
+pub type Line = Rc<RefCell<Num>>;
 
-fn parse_input(input: &str) -> IResult<&str, Vec<Line>> {
+/// Parses one snailfish number per line
+pub fn parse_input(input: &str) -> IResult<&str, Vec<Line>> {
     let (input, lines) = separated_list1(newline, parse_line)(input)?;
     Ok((input, lines))
 }
 
-fn parse_line(input: &str) -> IResult<&str, Line> {
+/// Parses a single snailfish number and connects all parents
+pub fn parse_line(input: &str) -> IResult<&str, Line> {
     let (input, line) = parse_num(input)?;
     let rc = Rc::new(RefCell::new(line));
     connect(rc.clone());
@@ -603,4 +704,76 @@ mod tests {
         let result = process_part2(input);
         assert_eq!(result, "3993");
     }
+
+    #[test]
+    fn test_steps() {
+        let (_, left) = parse_line("[[[[4,3],4],4],[7,[[8,4],9]]]").unwrap();
+        let (_, right) = parse_line("[1,1]").unwrap();
+        let num = add(left, right);
+        assert_eq!(
+            format!("{}", num.borrow()),
+            "[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]"
+        );
+        assert!(explode_once(num.clone()));
+        assert_eq!(
+            format!("{}", num.borrow()),
+            "[[[[0,7],4],[7,[[8,4],9]]],[1,1]]"
+        );
+        assert!(explode_once(num.clone()));
+        assert_eq!(
+            format!("{}", num.borrow()),
+            "[[[[0,7],4],[15,[0,13]]],[1,1]]"
+        );
+        assert!(!explode_once(num.clone()));
+        assert!(split(num.clone()));
+        assert_eq!(
+            format!("{}", num.borrow()),
+            "[[[[0,7],4],[[7,8],[0,13]]],[1,1]]"
+        );
+        assert!(split(num.clone()));
+        assert_eq!(
+            format!("{}", num.borrow()),
+            "[[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]"
+        );
+        assert!(explode_once(num.clone()));
+        assert_eq!(
+            format!("{}", num.borrow()),
+            "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]"
+        );
+        assert!(!explode_once(num.clone()));
+        assert!(!split(num));
+    }
+
+    /// Adds two numbers and records the number after every reduction action
+    fn reduction_steps(left: &str, right: &str) -> String {
+        let (_, left) = parse_line(left).unwrap();
+        let (_, right) = parse_line(right).unwrap();
+        let num = add(left, right);
+        let mut steps = format!("after addition: {}\n", num.borrow());
+        loop {
+            let action = if explode_once(num.clone()) {
+                "explode"
+            } else if split(num.clone()) {
+                "split"
+            } else {
+                break;
+            };
+            steps.push_str(&format!("after {}: {}\n", action, num.borrow()));
+        }
+        steps.push_str(&format!("magnitude: {}\n", num.borrow().magnitude()));
+        steps
+    }
+
+    #[test]
+    fn reduction_snapshot() {
+        insta::assert_snapshot!(reduction_steps("[[[[4,3],4],4],[7,[[8,4],9]]]", "[1,1]"));
+    }
+
+    #[test]
+    fn reduction_snapshot_larger() {
+        insta::assert_snapshot!(reduction_steps(
+            "[[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]]",
+            "[7,[[[3,7],[4,3]],[[6,3],[8,8]]]]"
+        ));
+    }
 }
@@ -1,41 +1,50 @@
+//! Day 19: Beacon Scanner
+
 use nom::{
-    branch::alt,
     bytes::complete::tag,
     character::complete::char as nomchar,
-    character::complete::{
-        alpha1, i32 as nom_i32, newline, one_of, space0, space1, u32 as nom_u32,
-    },
-    combinator::recognize,
-    multi::{many1, separated_list1},
+    character::complete::{i32 as nom_i32, newline, u32 as nom_u32},
+    multi::separated_list1,
     sequence::{delimited, tuple},
     IResult,
 };
 
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(19);
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part1(input: &str) -> String {
-    let (input, scanners) = parse_input(input).unwrap();
+    let (input, _scanners) = parse_input(input).unwrap();
     assert!(input.is_empty());
-    dbg!(scanners);
+    #[cfg(feature = "trace")]
+    tracing::debug!(scanners = _scanners.len(), "parsed");
     "".to_string()
 }
 
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
 pub fn process_part2(input: &str) -> String {
     "".to_string()
 }
 
+/// Position relative to a scanner
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-struct Coord {
-    x: i32,
-    y: i32,
-    z: i32,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Coord {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
 }
 
+/// A scanner and the beacons it detects
 #[derive(Debug)]
-struct Scanner {
-    id: u32,
-    coords: Vec<Coord>,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Scanner {
+    pub id: u32,
+    pub coords: Vec<Coord>,
 }
 
-fn parse_input(input: &str) -> IResult<&str, Vec<Scanner>> {
+/// Parses the blank line separated scanner reports
+pub fn parse_input(input: &str) -> IResult<&str, Vec<Scanner>> {
     let (input, lines) = separated_list1(tag("\n\n"), parse_scanner)(input)?;
     Ok((input, lines))
 }
@@ -0,0 +1,184 @@
+//! Day 20: Trench Map
+
+use std::{collections::HashSet, fmt::Display};
+
+use aoc_parse::lines_of;
+use nom::{
+    character::complete::{newline, one_of},
+    combinator::map,
+    multi::{count, many1},
+    sequence::{pair, terminated},
+    IResult,
+};
+
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(20);
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
+pub fn process_part1(input: &str) -> String {
+    let (_, (algorithm, image)) = parse_input(input).unwrap();
+    enhance(image, &algorithm, 2).to_string()
+}
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
+pub fn process_part2(input: &str) -> String {
+    let (_, (algorithm, image)) = parse_input(input).unwrap();
+    enhance(image, &algorithm, 50).to_string()
+}
+
+/// Number of lit pixels after enhancing `image` `steps` times
+fn enhance(mut image: Image, algorithm: &[bool], steps: usize) -> usize {
+    for _step in 0..steps {
+        image = image.enhance(algorithm);
+        #[cfg(feature = "trace")]
+        tracing::trace!(
+            step = _step + 1,
+            pixels = image.pixels.len(),
+            background = image.background,
+            "enhanced"
+        );
+    }
+    image
+        .lit()
+        .expect("Should be a finite number of lit pixels!")
+}
+
+/// Enhancement algorithm, whether the pixel with a given 9-bit neighbourhood is lit
+pub type Algorithm = Vec<bool>;
+
+/// Infinite image, stored as the pixels that differ from the background
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    /// (x, y) of every pixel that isn't the background colour
+    pixels: HashSet<(i64, i64)>,
+    /// Whether the infinite background is lit
+    pub background: bool,
+}
+
+impl Image {
+    /// Image from rows of pixels on a dark background, the first row at y = 0
+    pub fn new(rows: &[Vec<bool>]) -> Self {
+        let pixels = rows
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, &lit)| lit)
+                    .map(move |(x, _)| (x as i64, y as i64))
+            })
+            .collect();
+        Self {
+            pixels,
+            background: false,
+        }
+    }
+
+    pub fn get(&self, x: i64, y: i64) -> bool {
+        self.pixels.contains(&(x, y)) != self.background
+    }
+
+    /// Corners of the smallest rectangle outside of which every pixel is background
+    fn bounds(&self) -> Option<((i64, i64), (i64, i64))> {
+        let xs = self.pixels.iter().map(|&(x, _)| x);
+        let ys = self.pixels.iter().map(|&(_, y)| y);
+        Some((
+            (xs.clone().min()?, ys.clone().min()?),
+            (xs.max()?, ys.max()?),
+        ))
+    }
+
+    /// Index into the algorithm for the 3x3 square around (x, y)
+    fn index(&self, x: i64, y: i64) -> usize {
+        (y - 1..=y + 1)
+            .flat_map(|y| (x - 1..=x + 1).map(move |x| (x, y)))
+            .fold(0, |index, (x, y)| index << 1 | self.get(x, y) as usize)
+    }
+
+    /// Applies the algorithm to every pixel at once
+    pub fn enhance(&self, algorithm: &[bool]) -> Self {
+        // far away pixels only see background, so it becomes the first or last entry
+        let background = algorithm[if self.background { 511 } else { 0 }];
+        let mut pixels = HashSet::new();
+        if let Some(((min_x, min_y), (max_x, max_y))) = self.bounds() {
+            for y in min_y - 1..=max_y + 1 {
+                for x in min_x - 1..=max_x + 1 {
+                    if algorithm[self.index(x, y)] != background {
+                        pixels.insert((x, y));
+                    }
+                }
+            }
+        }
+        Self { pixels, background }
+    }
+
+    /// Number of lit pixels, `None` while the infinite background is lit
+    pub fn lit(&self) -> Option<usize> {
+        (!self.background).then_some(self.pixels.len())
+    }
+}
+
+impl Display for Image {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(((min_x, min_y), (max_x, max_y))) = self.bounds() {
+            for y in min_y..=max_y {
+                for x in min_x..=max_x {
+                    write!(f, "{}", if self.get(x, y) { '#' } else { '.' })?;
+                }
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses the 512 pixel algorithm, a blank line and the input image
+pub fn parse_input(input: &str) -> IResult<&str, (Algorithm, Image)> {
+    let (input, algorithm) = terminated(count(parse_pixel, 512), pair(newline, newline))(input)?;
+    let (input, rows) = lines_of(many1(parse_pixel))(input)?;
+    Ok((input, (algorithm, Image::new(&rows))))
+}
+
+fn parse_pixel(input: &str) -> IResult<&str, bool> {
+    map(one_of("#."), |c| c == '#')(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "..#.#..#####.#.#.#.###.##.....###.##.#..###.####..#####..#....#..#..##..###..######.###...####..#..#####..##..#.#####...##.#.#..#.##..#.#......#.###.######.###.####...#.##.##..#..#..#####.....#.#....###..#.##......#.....#..#..#..##..#...##.######.####.####.#.#...#.......#..#.#.#...####.##.#......#..#...##.#.##..#...##.#.##..###.#......#.#.......#.#.#.####.###.##...#.....####.#..#..#.##.#....##..#.####....##...##..#...#......#.#.......#.......##..####..#...#.#.#...##..#.#..###..#####........#..####......#..#
+
+#..#.
+#....
+##..#
+..#..
+..###";
+
+    #[test]
+    fn part1() {
+        let result = process_part1(INPUT);
+        assert_eq!(result, "35");
+    }
+
+    #[test]
+    fn part2() {
+        let result = process_part2(INPUT);
+        assert_eq!(result, "3351");
+    }
+
+    #[test]
+    fn flipping_background() {
+        let mut algorithm = vec![false; 512];
+        algorithm[0] = true;
+        let image = Image::new(&[vec![true]]);
+        let once = image.enhance(&algorithm);
+        assert!(once.background);
+        assert_eq!(once.lit(), None);
+        assert_eq!(once.to_string(), "...\n...\n...\n");
+        let twice = once.enhance(&algorithm);
+        assert_eq!(twice.lit(), Some(1));
+        assert!(twice.get(0, 0));
+        assert!(!twice.get(100, -100));
+    }
+}
@@ -0,0 +1,185 @@
+//! Day 21: Dirac Dice
+
+use std::collections::HashMap;
+
+use aoc_parse::unsigned;
+use nom::{
+    bytes::complete::tag,
+    character::complete::newline,
+    combinator::verify,
+    sequence::{preceded, separated_pair},
+    IResult,
+};
+
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(21);
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
+pub fn process_part1(input: &str) -> String {
+    let (_, start) = parse_input(input).unwrap();
+    Rules::PRACTICE.deterministic(start).to_string()
+}
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
+pub fn process_part2(input: &str) -> String {
+    let (_, start) = parse_input(input).unwrap();
+    let wins = Rules::DIRAC.dirac(start);
+    wins.into_iter().max().unwrap().to_string()
+}
+
+/// Starting positions of both players
+pub type Start = [u32; 2];
+
+/// Number of spaces on the board of both games
+pub const BOARD: u32 = 10;
+
+/// Ways to roll each total with three throws of the Dirac die, from 3 to 9
+const DIRAC_ROLLS: [(u32, u64); 7] = [(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)];
+
+/// Positions and scores of both players and whose turn it is
+type State = ([u32; 2], [u32; 2], usize);
+
+/// Board size and the score that wins the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    /// Number of spaces, numbered from 1
+    pub board: u32,
+    pub target: u32,
+}
+
+impl Rules {
+    /// The warm-up game with the deterministic die
+    pub const PRACTICE: Rules = Rules {
+        board: BOARD,
+        target: 1000,
+    };
+    /// The game with the Dirac die
+    pub const DIRAC: Rules = Rules {
+        board: BOARD,
+        target: 21,
+    };
+
+    /// Space reached by moving `steps` from `position`, at least one step
+    fn advance(&self, position: u32, steps: u32) -> u32 {
+        (position + steps - 1) % self.board + 1
+    }
+
+    /// Plays with the deterministic 100-sided die, returns the losing score times the number of rolls
+    pub fn deterministic(&self, start: Start) -> u64 {
+        let mut positions = start;
+        let mut scores = [0; 2];
+        let mut die = (1..=100).cycle();
+        let mut rolls = 0;
+        for player in [0, 1].into_iter().cycle() {
+            let steps: u32 = die.by_ref().take(3).sum();
+            rolls += 3;
+            positions[player] = self.advance(positions[player], steps);
+            scores[player] += positions[player];
+            if scores[player] >= self.target {
+                #[cfg(feature = "trace")]
+                tracing::debug!(winner = player + 1, rolls, ?scores, "game over");
+                return scores[1 - player] as u64 * rolls;
+            }
+        }
+        unreachable!()
+    }
+
+    /// Number of universes in which each player wins with the Dirac die
+    pub fn dirac(&self, start: Start) -> [u64; 2] {
+        let mut cache = HashMap::new();
+        let wins = self.universes((start, [0, 0], 0), &mut cache);
+        #[cfg(feature = "trace")]
+        tracing::debug!(states = cache.len(), ?wins, "counted universes");
+        wins
+    }
+
+    fn universes(&self, state: State, cache: &mut HashMap<State, [u64; 2]>) -> [u64; 2] {
+        if let Some(&wins) = cache.get(&state) {
+            return wins;
+        }
+        let (positions, scores, player) = state;
+        let mut wins = [0, 0];
+        for (steps, ways) in DIRAC_ROLLS {
+            let (mut positions, mut scores) = (positions, scores);
+            positions[player] = self.advance(positions[player], steps);
+            scores[player] += positions[player];
+            if scores[player] >= self.target {
+                wins[player] += ways;
+            } else {
+                let [first, second] = self.universes((positions, scores, 1 - player), cache);
+                wins[0] += first * ways;
+                wins[1] += second * ways;
+            }
+        }
+        cache.insert(state, wins);
+        wins
+    }
+}
+
+/// Parses both players' starting positions, spaces on the board
+pub fn parse_input(input: &str) -> IResult<&str, Start> {
+    let position = || verify(unsigned, |p: &u32| (1..=BOARD).contains(p));
+    let (input, (first, second)) = separated_pair(
+        preceded(tag("Player 1 starting position: "), position()),
+        newline,
+        preceded(tag("Player 2 starting position: "), position()),
+    )(input)?;
+    Ok((input, [first, second]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "Player 1 starting position: 4
+Player 2 starting position: 8";
+
+    #[test]
+    fn part1() {
+        let result = process_part1(INPUT);
+        assert_eq!(result, "739785");
+    }
+
+    #[test]
+    fn part2() {
+        let result = process_part2(INPUT);
+        assert_eq!(result, "444356092776315");
+        assert_eq!(Rules::DIRAC.dirac([4, 8])[1], 341960390180808);
+    }
+
+    #[test]
+    fn rules() {
+        // every first move scores at least one point
+        let rules = Rules {
+            board: 10,
+            target: 1,
+        };
+        assert_eq!(rules.dirac([4, 8]), [27, 0]);
+        let small = Rules {
+            board: 3,
+            target: 1000,
+        };
+        assert_eq!(small.advance(3, 1), 1);
+        assert_eq!(small.advance(2, 9), 2);
+        // 4 -> 10, 8 -> 3, 10 -> 4: player 1 wins with 14 after nine rolls
+        let quick = Rules {
+            board: 10,
+            target: 11,
+        };
+        assert_eq!(quick.deterministic([4, 8]), 3 * 9);
+    }
+
+    #[test]
+    fn off_the_board() {
+        assert!(
+            parse_input("Player 1 starting position: 0\nPlayer 2 starting position: 8").is_err()
+        );
+        assert!(
+            parse_input("Player 1 starting position: 4\nPlayer 2 starting position: 11").is_err()
+        );
+        assert_eq!(
+            parse_input("Player 1 starting position: 10\nPlayer 2 starting position: 1"),
+            Ok(("", [10, 1]))
+        );
+    }
+}
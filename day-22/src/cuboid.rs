@@ -0,0 +1,134 @@
+//! Axis-aligned cuboids of integer cubes and sets of them.
+//!
+//! A [`CuboidSet`] keeps signed cuboids: adding a cuboid first cancels its
+//! overlap with everything already in the set, so the signed volumes always
+//! sum to the number of cubes in the union.
+
+use std::collections::HashMap;
+
+/// Cubes from `min` to `max` inclusive, along x, y and z
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cuboid {
+    pub min: [i64; 3],
+    pub max: [i64; 3],
+}
+
+impl Cuboid {
+    /// Cuboid between two opposite corners
+    pub fn new(a: [i64; 3], b: [i64; 3]) -> Self {
+        Self {
+            min: [0, 1, 2].map(|i| a[i].min(b[i])),
+            max: [0, 1, 2].map(|i| a[i].max(b[i])),
+        }
+    }
+
+    /// Number of cubes
+    pub fn volume(&self) -> i64 {
+        (0..3).map(|i| self.max[i] - self.min[i] + 1).product()
+    }
+
+    /// Cubes in both cuboids, `None` if they don't overlap
+    pub fn intersection(&self, other: &Cuboid) -> Option<Cuboid> {
+        let min = [0, 1, 2].map(|i| self.min[i].max(other.min[i]));
+        let max = [0, 1, 2].map(|i| self.max[i].min(other.max[i]));
+        (0..3)
+            .all(|i| min[i] <= max[i])
+            .then_some(Cuboid { min, max })
+    }
+
+    pub fn contains(&self, cube: [i64; 3]) -> bool {
+        (0..3).all(|i| (self.min[i]..=self.max[i]).contains(&cube[i]))
+    }
+}
+
+/// Union of cuboids, as cuboids counted with a multiplicity
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CuboidSet {
+    signed: HashMap<Cuboid, i64>,
+}
+
+impl CuboidSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the overlap of `cuboid` with the set
+    fn cut(&mut self, cuboid: &Cuboid) {
+        let overlaps: Vec<(Cuboid, i64)> = self
+            .signed
+            .iter()
+            .filter_map(|(c, &sign)| c.intersection(cuboid).map(|overlap| (overlap, sign)))
+            .collect();
+        for (overlap, sign) in overlaps {
+            *self.signed.entry(overlap).or_insert(0) -= sign;
+        }
+        self.signed.retain(|_, sign| *sign != 0);
+    }
+
+    /// Adds all cubes of `cuboid`
+    pub fn insert(&mut self, cuboid: Cuboid) {
+        self.cut(&cuboid);
+        *self.signed.entry(cuboid).or_insert(0) += 1;
+    }
+
+    /// Removes all cubes of `cuboid`
+    pub fn remove(&mut self, cuboid: Cuboid) {
+        self.cut(&cuboid);
+    }
+
+    /// Number of cubes in the set
+    pub fn volume(&self) -> i64 {
+        self.signed.iter().map(|(c, sign)| c.volume() * sign).sum()
+    }
+
+    pub fn contains(&self, cube: [i64; 3]) -> bool {
+        let count: i64 = self
+            .signed
+            .iter()
+            .filter(|(c, _)| c.contains(cube))
+            .map(|(_, sign)| sign)
+            .sum();
+        count > 0
+    }
+
+    /// Number of signed cuboids kept
+    pub fn len(&self) -> usize {
+        self.signed.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.signed.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intersection() {
+        let a = Cuboid::new([0, 0, 0], [2, 2, 2]);
+        let b = Cuboid::new([3, 3, 3], [1, 1, 1]);
+        assert_eq!(a.volume(), 27);
+        assert_eq!(a.intersection(&b), Some(Cuboid::new([1, 1, 1], [2, 2, 2])));
+        assert_eq!(a.intersection(&Cuboid::new([3, 0, 0], [4, 2, 2])), None);
+        assert!(b.contains([3, 1, 2]));
+        assert!(!b.contains([0, 1, 2]));
+    }
+
+    #[test]
+    fn set() {
+        let mut set = CuboidSet::new();
+        set.insert(Cuboid::new([0, 0, 0], [2, 2, 2]));
+        set.insert(Cuboid::new([1, 1, 1], [3, 3, 3]));
+        assert_eq!(set.volume(), 27 + 27 - 8);
+        set.remove(Cuboid::new([1, 1, 1], [1, 1, 1]));
+        assert_eq!(set.volume(), 45);
+        assert!(!set.contains([1, 1, 1]));
+        assert!(set.contains([2, 2, 2]));
+        set.insert(Cuboid::new([0, 0, 0], [3, 3, 3]));
+        assert_eq!(set.volume(), 64);
+        set.remove(Cuboid::new([-1, -1, -1], [4, 4, 4]));
+        assert!(set.is_empty());
+    }
+}
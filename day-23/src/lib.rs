@@ -0,0 +1,558 @@
+//! Day 23: Amphipod
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    fmt::Display,
+};
+
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{newline, one_of, space0},
+    combinator::{map, opt},
+    multi::{count, many_m_n},
+    sequence::{delimited, pair, preceded, terminated},
+    IResult,
+};
+
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(23);
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
+pub fn process_part1(input: &str) -> String {
+    let (_, burrow) = parse_input(input).unwrap();
+    let solution = burrow.organize().expect("Should be solvable!");
+    solution.energy.to_string()
+}
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
+pub fn process_part2(input: &str) -> String {
+    let (_, burrow) = parse_input(input).unwrap();
+    let solution = burrow.unfold().organize().expect("Should be solvable!");
+    solution.energy.to_string()
+}
+
+/// Amphipod type, 0 for Amber up to 3 for Desert
+pub type Amphipod = u8;
+
+const HALLWAY: usize = 11;
+const ROOMS: usize = 4;
+/// Deepest rooms whose burrow still fits in a [`State`]
+pub const MAX_DEPTH: usize = (State::BITS as usize / 3 - HALLWAY) / ROOMS;
+/// Hallway spaces that aren't right outside a room
+const STOPS: [usize; 7] = [0, 1, 3, 5, 7, 9, 10];
+
+/// Energy one step of `amphipod` takes
+pub fn energy(amphipod: Amphipod) -> u64 {
+    10_u64.pow(amphipod as u32)
+}
+
+fn letter(amphipod: Amphipod) -> char {
+    (b'A' + amphipod) as char
+}
+
+/// Hallway space in front of `room`
+fn door(room: usize) -> usize {
+    2 + 2 * room
+}
+
+/// A space in the burrow
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Space {
+    /// Hallway space from the left
+    Hallway(usize),
+    /// Room from the left and level from the top
+    Room(usize, usize),
+}
+
+impl Display for Space {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Space::Hallway(x) => write!(f, "hallway {}", x),
+            Space::Room(room, level) => write!(f, "room {} level {}", room + 1, level + 1),
+        }
+    }
+}
+
+/// An amphipod moving from one space to another
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub amphipod: Amphipod,
+    pub from: Space,
+    pub to: Space,
+    pub energy: u64,
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} from {} to {} ({} energy)",
+            letter(self.amphipod),
+            self.from,
+            self.to,
+            self.energy
+        )
+    }
+}
+
+/// Cheapest way to organize a burrow
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    pub energy: u64,
+    pub moves: Vec<Move>,
+}
+
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, step) in self.moves.iter().enumerate() {
+            writeln!(f, "{:>3}. {}", i + 1, step)?;
+        }
+        writeln!(f, "total: {} energy", self.energy)
+    }
+}
+
+/// The hallway and the rooms with the amphipods in them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Burrow {
+    pub hallway: Vec<Option<Amphipod>>,
+    /// Spaces of every room, from the top down
+    pub rooms: Vec<Vec<Option<Amphipod>>>,
+}
+
+impl Burrow {
+    pub fn depth(&self) -> usize {
+        self.rooms[0].len()
+    }
+
+    /// Inserts the two folded lines of part 2 in the middle of the rooms
+    pub fn unfold(&self) -> Self {
+        let mut burrow = self.clone();
+        for (room, inserted) in burrow
+            .rooms
+            .iter_mut()
+            .zip([[3, 3], [2, 1], [1, 0], [0, 2]])
+        {
+            room.splice(1..1, inserted.map(Some));
+        }
+        burrow
+    }
+
+    fn get(&self, space: Space) -> Option<Amphipod> {
+        match space {
+            Space::Hallway(x) => self.hallway[x],
+            Space::Room(room, level) => self.rooms[room][level],
+        }
+    }
+
+    fn set(&mut self, space: Space, amphipod: Option<Amphipod>) {
+        match space {
+            Space::Hallway(x) => self.hallway[x] = amphipod,
+            Space::Room(room, level) => self.rooms[room][level] = amphipod,
+        }
+    }
+
+    /// Performs `step`, panics if the amphipod isn't where it's supposed to be
+    pub fn apply(&mut self, step: &Move) {
+        assert_eq!(self.get(step.from), Some(step.amphipod), "Should be there!");
+        assert_eq!(self.get(step.to), None, "Should be free!");
+        self.set(step.from, None);
+        self.set(step.to, Some(step.amphipod));
+    }
+
+    pub fn is_organized(&self) -> bool {
+        self.rooms
+            .iter()
+            .enumerate()
+            .all(|(room, spaces)| spaces.iter().all(|&a| a == Some(room as Amphipod)))
+    }
+
+    /// Cheapest sequence of moves that gets every amphipod into its own room
+    pub fn organize(&self) -> Result<Solution, String> {
+        let depth = self.depth();
+        if depth > MAX_DEPTH {
+            return Err(format!(
+                "rooms are {} deep, at most {} fit",
+                depth, MAX_DEPTH
+            ));
+        }
+        if self.hallway.len() != HALLWAY
+            || self.rooms.len() != ROOMS
+            || self.rooms.iter().any(|room| room.len() != depth)
+        {
+            return Err("burrow isn't shaped like the diagram".to_string());
+        }
+        let spaces = self.hallway.iter().chain(self.rooms.iter().flatten());
+        for kind in 0..ROOMS as Amphipod {
+            let found = spaces.clone().filter(|&&a| a == Some(kind)).count();
+            if found != depth {
+                return Err(format!(
+                    "there are {} amphipods of type {} instead of {}",
+                    found,
+                    letter(kind),
+                    depth
+                ));
+            }
+        }
+        let layout = Layout { depth };
+        layout
+            .search(layout.encode(self))
+            .ok_or("burrow can't be organized".to_string())
+    }
+}
+
+impl Display for Burrow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let show = |space: Option<Amphipod>| space.map_or('.', letter);
+        writeln!(f, "#############")?;
+        write!(f, "#")?;
+        for &space in self.hallway.iter() {
+            write!(f, "{}", show(space))?;
+        }
+        writeln!(f, "#")?;
+        for level in 0..self.depth() {
+            write!(f, "{}", if level == 0 { "###" } else { "  #" })?;
+            for room in self.rooms.iter() {
+                write!(f, "{}#", show(room[level]))?;
+            }
+            writeln!(f, "{}", if level == 0 { "##" } else { "" })?;
+        }
+        writeln!(f, "  #########")
+    }
+}
+
+/// Burrow packed into 3 bits per space: the hallway, then every room from the top down
+type State = u128;
+
+/// Least energy found for every state and the move that got there
+type Visited = HashMap<State, (u64, Option<(State, Move)>)>;
+
+/// Encoding of burrows with rooms of the same depth
+struct Layout {
+    depth: usize,
+}
+
+impl Layout {
+    fn index(&self, space: Space) -> usize {
+        match space {
+            Space::Hallway(x) => x,
+            Space::Room(room, level) => HALLWAY + room * self.depth + level,
+        }
+    }
+
+    fn get(&self, state: State, space: Space) -> Option<Amphipod> {
+        match (state >> (3 * self.index(space))) & 0b111 {
+            0 => None,
+            a => Some(a as Amphipod - 1),
+        }
+    }
+
+    fn set(&self, state: State, space: Space, amphipod: Option<Amphipod>) -> State {
+        let shift = 3 * self.index(space);
+        let value = amphipod.map_or(0, |a| a as State + 1);
+        state & !(0b111 << shift) | value << shift
+    }
+
+    fn encode(&self, burrow: &Burrow) -> State {
+        let mut state = 0;
+        for (x, &amphipod) in burrow.hallway.iter().enumerate() {
+            state = self.set(state, Space::Hallway(x), amphipod);
+        }
+        for (room, spaces) in burrow.rooms.iter().enumerate() {
+            for (level, &amphipod) in spaces.iter().enumerate() {
+                state = self.set(state, Space::Room(room, level), amphipod);
+            }
+        }
+        state
+    }
+
+    fn goal(&self) -> State {
+        let mut state = 0;
+        for room in 0..ROOMS {
+            for level in 0..self.depth {
+                state = self.set(state, Space::Room(room, level), Some(room as Amphipod));
+            }
+        }
+        state
+    }
+
+    /// Whether `room` from `level` down only holds amphipods that belong there
+    fn settled(&self, state: State, room: usize, level: usize) -> bool {
+        (level..self.depth).all(|l| self.get(state, Space::Room(room, l)) == Some(room as Amphipod))
+    }
+
+    /// Whether the hallway between `from` and `to` is free, not counting `from` itself
+    fn clear(&self, state: State, from: usize, to: usize) -> bool {
+        let path = if from < to {
+            from + 1..=to
+        } else {
+            to..=from - 1
+        };
+        path.into_iter()
+            .all(|x| self.get(state, Space::Hallway(x)).is_none())
+    }
+
+    fn step(
+        &self,
+        state: State,
+        amphipod: Amphipod,
+        from: Space,
+        to: Space,
+        steps: usize,
+    ) -> (State, Move) {
+        let next = self.set(self.set(state, from, None), to, Some(amphipod));
+        let energy = steps as u64 * energy(amphipod);
+        (
+            next,
+            Move {
+                amphipod,
+                from,
+                to,
+                energy,
+            },
+        )
+    }
+
+    /// Every move allowed from `state`
+    fn moves(&self, state: State) -> Vec<(State, Move)> {
+        // an amphipod that can go home might as well do so right away
+        for x in 0..HALLWAY {
+            let Some(amphipod) = self.get(state, Space::Hallway(x)) else {
+                continue;
+            };
+            let room = amphipod as usize;
+            let spaces = (0..self.depth).map(|level| self.get(state, Space::Room(room, level)));
+            if spaces.clone().any(|a| a.is_some_and(|a| a != amphipod))
+                || !self.clear(state, x, door(room))
+            {
+                continue;
+            }
+            let level = spaces.take_while(|a| a.is_none()).count() - 1;
+            let steps = x.abs_diff(door(room)) + level + 1;
+            return vec![self.step(
+                state,
+                amphipod,
+                Space::Hallway(x),
+                Space::Room(room, level),
+                steps,
+            )];
+        }
+
+        let mut moves = Vec::new();
+        for room in 0..ROOMS {
+            let Some(level) =
+                (0..self.depth).find(|&l| self.get(state, Space::Room(room, l)).is_some())
+            else {
+                continue;
+            };
+            if self.settled(state, room, level) {
+                continue;
+            }
+            let amphipod = self.get(state, Space::Room(room, level)).unwrap();
+            for x in STOPS {
+                if self.clear(state, door(room), x) {
+                    let steps = level + 1 + x.abs_diff(door(room));
+                    moves.push(self.step(
+                        state,
+                        amphipod,
+                        Space::Room(room, level),
+                        Space::Hallway(x),
+                        steps,
+                    ));
+                }
+            }
+        }
+        moves
+    }
+
+    /// Lower bound of the energy still needed, moving through each other
+    fn estimate(&self, state: State) -> u64 {
+        let mut estimate = 0;
+        for x in 0..HALLWAY {
+            if let Some(amphipod) = self.get(state, Space::Hallway(x)) {
+                let steps = x.abs_diff(door(amphipod as usize)) + 1;
+                estimate += steps as u64 * energy(amphipod);
+            }
+        }
+        for room in 0..ROOMS {
+            for level in 0..self.depth {
+                let Some(amphipod) = self.get(state, Space::Room(room, level)) else {
+                    continue;
+                };
+                let steps = if amphipod as usize != room {
+                    level + 1 + door(room).abs_diff(door(amphipod as usize)) + 1
+                } else if !self.settled(state, room, level) {
+                    // out, aside, back and in again
+                    level + 1 + 2 + 1
+                } else {
+                    0
+                };
+                estimate += steps as u64 * energy(amphipod);
+            }
+        }
+        estimate
+    }
+
+    /// A* from `start` to the organized burrow
+    fn search(&self, start: State) -> Option<Solution> {
+        let goal = self.goal();
+        let mut best: Visited = HashMap::new();
+        let mut queue = BinaryHeap::new();
+        best.insert(start, (0, None));
+        queue.push(Reverse((self.estimate(start), 0, start)));
+        while let Some(Reverse((_, energy, state))) = queue.pop() {
+            if state == goal {
+                #[cfg(feature = "trace")]
+                tracing::debug!(energy, states = best.len(), "organized");
+                return Some(self.solution(&best, goal));
+            }
+            if energy > best[&state].0 {
+                continue;
+            }
+            for (next, step) in self.moves(state) {
+                let next_energy = energy + step.energy;
+                if best.get(&next).is_some_and(|&(e, _)| e <= next_energy) {
+                    continue;
+                }
+                best.insert(next, (next_energy, Some((state, step))));
+                queue.push(Reverse((
+                    next_energy + self.estimate(next),
+                    next_energy,
+                    next,
+                )));
+            }
+        }
+        None
+    }
+
+    fn solution(&self, best: &Visited, goal: State) -> Solution {
+        let mut moves = Vec::new();
+        let mut state = goal;
+        while let Some((previous, step)) = best[&state].1 {
+            moves.push(step);
+            state = previous;
+        }
+        moves.reverse();
+        Solution {
+            energy: best[&goal].0,
+            moves,
+        }
+    }
+}
+
+/// Parses the burrow diagram, with rooms up to [`MAX_DEPTH`] deep
+pub fn parse_input(input: &str) -> IResult<&str, Burrow> {
+    let (input, _) = terminated(tag("#############"), newline)(input)?;
+    let (input, hallway) = delimited(
+        tag("#"),
+        count(parse_space, HALLWAY),
+        pair(tag("#"), newline),
+    )(input)?;
+    let (input, rows) = many_m_n(1, MAX_DEPTH, terminated(parse_row, newline))(input)?;
+    let (input, _) = preceded(space0, tag("#########"))(input)?;
+    let rooms = (0..ROOMS)
+        .map(|room| rows.iter().map(|row| row[room]).collect())
+        .collect();
+    Ok((input, Burrow { hallway, rooms }))
+}
+
+/// One level of all four rooms
+fn parse_row(input: &str) -> IResult<&str, Vec<Option<Amphipod>>> {
+    delimited(
+        pair(space0, alt((tag("###"), tag("#")))),
+        count(terminated(parse_space, tag("#")), ROOMS),
+        pair(opt(tag("##")), space0),
+    )(input)
+}
+
+fn parse_space(input: &str) -> IResult<&str, Option<Amphipod>> {
+    map(one_of(".ABCD"), |c| match c {
+        '.' => None,
+        c => Some(c as u8 - b'A'),
+    })(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########";
+
+    #[test]
+    fn part1() {
+        let result = process_part1(INPUT);
+        assert_eq!(result, "12521");
+    }
+
+    #[test]
+    fn part2() {
+        let result = process_part2(INPUT);
+        assert_eq!(result, "44169");
+    }
+
+    #[test]
+    fn diagram() {
+        let (_, burrow) = parse_input(INPUT).unwrap();
+        assert_eq!(burrow.to_string(), format!("{}\n", INPUT));
+        let unfolded = burrow.unfold();
+        assert_eq!(unfolded.depth(), 4);
+        assert!(unfolded
+            .to_string()
+            .contains("  #D#C#B#A#\n  #D#B#A#C#\n  #A#D#C#A#"));
+        let (_, parsed) = parse_input(&unfolded.to_string()).unwrap();
+        assert_eq!(parsed, unfolded);
+    }
+
+    #[test]
+    fn moves() {
+        let (_, burrow) = parse_input(INPUT).unwrap();
+        let solution = burrow.organize().unwrap();
+        let mut replayed = burrow.clone();
+        for step in solution.moves.iter() {
+            replayed.apply(step);
+        }
+        assert!(replayed.is_organized());
+        assert_eq!(
+            solution.moves.iter().map(|m| m.energy).sum::<u64>(),
+            solution.energy
+        );
+        assert!(solution.to_string().ends_with("total: 12521 energy\n"));
+        let organized = Burrow {
+            hallway: vec![None; HALLWAY],
+            rooms: (0..4).map(|room| vec![Some(room); 2]).collect(),
+        };
+        assert_eq!(organized.organize().unwrap().moves, vec![]);
+    }
+
+    #[test]
+    fn depth_limit() {
+        assert_eq!(MAX_DEPTH, 7);
+        let diagram = |depth: usize| {
+            let mut diagram = "#############\n#...........#\n###A#B#C#D###\n".to_string();
+            diagram += &"  #A#B#C#D#\n".repeat(depth - 1);
+            diagram + "  #########"
+        };
+        let (_, deepest) = parse_input(&diagram(MAX_DEPTH)).unwrap();
+        assert_eq!(deepest.depth(), 7);
+        assert_eq!(deepest.organize().unwrap().energy, 0);
+        assert!(parse_input(&diagram(MAX_DEPTH + 1)).is_err());
+        let (_, burrow) = parse_input(&diagram(6)).unwrap();
+        assert_eq!(
+            burrow.unfold().organize(),
+            Err("rooms are 8 deep, at most 7 fit".to_string())
+        );
+        // a third Amber amphipod waiting for a room that's already full of them
+        let (_, crowded) = parse_input(&diagram(2).replacen("#...", "#A..", 1)).unwrap();
+        assert_eq!(
+            crowded.organize(),
+            Err("there are 3 amphipods of type A instead of 2".to_string())
+        );
+        let (_, missing) = parse_input(&diagram(2).replacen("#A#B", "#.#B", 1)).unwrap();
+        assert!(missing.organize().is_err());
+    }
+}
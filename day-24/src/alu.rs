@@ -0,0 +1,276 @@
+//! The submarine's arithmetic logic unit.
+//!
+//! Programs are parsed with [`parse_program`] and run on an [`Alu`], which
+//! reports invalid operations as an [`AluError`] instead of panicking.
+
+use std::fmt::Display;
+
+use aoc_parse::{lines_of, signed};
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{char as nomchar, one_of},
+    combinator::map,
+    sequence::{pair, preceded, separated_pair},
+    IResult,
+};
+
+/// One of the four registers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Register {
+    W,
+    X,
+    Y,
+    Z,
+}
+
+impl Register {
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl Display for Register {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Register::W => 'w',
+            Register::X => 'x',
+            Register::Y => 'y',
+            Register::Z => 'z',
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Second argument of an instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Register(Register),
+    Number(i64),
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operand::Register(register) => write!(f, "{}", register),
+            Operand::Number(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Inp(Register),
+    Add(Register, Operand),
+    Mul(Register, Operand),
+    Div(Register, Operand),
+    Mod(Register, Operand),
+    Eql(Register, Operand),
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Instruction::Inp(a) => write!(f, "inp {}", a),
+            Instruction::Add(a, b) => write!(f, "add {} {}", a, b),
+            Instruction::Mul(a, b) => write!(f, "mul {} {}", a, b),
+            Instruction::Div(a, b) => write!(f, "div {} {}", a, b),
+            Instruction::Mod(a, b) => write!(f, "mod {} {}", a, b),
+            Instruction::Eql(a, b) => write!(f, "eql {} {}", a, b),
+        }
+    }
+}
+
+/// Why a program couldn't run to the end, with the index of the failing instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AluError {
+    MissingInput(usize),
+    DivisionByZero(usize),
+    /// `mod` of a negative number or by a number that isn't positive
+    InvalidModulo(usize),
+    /// A result that doesn't fit in a register
+    Overflow(usize),
+}
+
+impl Display for AluError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AluError::MissingInput(i) => write!(f, "instruction {} needs more input", i),
+            AluError::DivisionByZero(i) => write!(f, "instruction {} divides by zero", i),
+            AluError::InvalidModulo(i) => write!(f, "instruction {} has an invalid modulo", i),
+            AluError::Overflow(i) => write!(f, "instruction {} overflows", i),
+        }
+    }
+}
+
+impl std::error::Error for AluError {}
+
+/// Registers w, x, y and z, all starting at 0
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Alu {
+    pub registers: [i64; 4],
+}
+
+impl Alu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, register: Register) -> i64 {
+        self.registers[register.index()]
+    }
+
+    fn value(&self, operand: Operand) -> i64 {
+        match operand {
+            Operand::Register(register) => self.get(register),
+            Operand::Number(n) => n,
+        }
+    }
+
+    /// Runs `program`, reading `inp` values from `input`
+    pub fn run<I>(&mut self, program: &[Instruction], input: I) -> Result<(), AluError>
+    where
+        I: IntoIterator<Item = i64>,
+    {
+        let mut input = input.into_iter();
+        for (i, &instruction) in program.iter().enumerate() {
+            let (target, value) = match instruction {
+                Instruction::Inp(a) => (a, input.next().ok_or(AluError::MissingInput(i))?),
+                Instruction::Add(a, b) => (
+                    a,
+                    self.get(a)
+                        .checked_add(self.value(b))
+                        .ok_or(AluError::Overflow(i))?,
+                ),
+                Instruction::Mul(a, b) => (
+                    a,
+                    self.get(a)
+                        .checked_mul(self.value(b))
+                        .ok_or(AluError::Overflow(i))?,
+                ),
+                Instruction::Div(a, b) => match self.value(b) {
+                    0 => return Err(AluError::DivisionByZero(i)),
+                    b => (a, self.get(a).checked_div(b).ok_or(AluError::Overflow(i))?),
+                },
+                Instruction::Mod(a, b) => match (self.get(a), self.value(b)) {
+                    (a_value, b) if a_value >= 0 && b > 0 => (a, a_value % b),
+                    _ => return Err(AluError::InvalidModulo(i)),
+                },
+                Instruction::Eql(a, b) => (a, (self.get(a) == self.value(b)) as i64),
+            };
+            self.registers[target.index()] = value;
+        }
+        Ok(())
+    }
+}
+
+/// Parses one instruction per line
+pub fn parse_program(input: &str) -> IResult<&str, Vec<Instruction>> {
+    lines_of(parse_instruction)(input)
+}
+
+fn parse_instruction(input: &str) -> IResult<&str, Instruction> {
+    let binary = |name, op: fn(Register, Operand) -> Instruction| {
+        map(
+            preceded(
+                pair(tag(name), nomchar(' ')),
+                separated_pair(parse_register, nomchar(' '), parse_operand),
+            ),
+            move |(a, b)| op(a, b),
+        )
+    };
+    alt((
+        map(preceded(tag("inp "), parse_register), Instruction::Inp),
+        binary("add", Instruction::Add),
+        binary("mul", Instruction::Mul),
+        binary("div", Instruction::Div),
+        binary("mod", Instruction::Mod),
+        binary("eql", Instruction::Eql),
+    ))(input)
+}
+
+fn parse_register(input: &str) -> IResult<&str, Register> {
+    map(one_of("wxyz"), |c| match c {
+        'w' => Register::W,
+        'x' => Register::X,
+        'y' => Register::Y,
+        _ => Register::Z,
+    })(input)
+}
+
+fn parse_operand(input: &str) -> IResult<&str, Operand> {
+    alt((
+        map(parse_register, Operand::Register),
+        map(signed, Operand::Number),
+    ))(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(program: &str, input: &[i64]) -> Result<Alu, AluError> {
+        let (rem, program) = parse_program(program).unwrap();
+        assert!(rem.is_empty());
+        let mut alu = Alu::new();
+        alu.run(&program, input.iter().copied())?;
+        Ok(alu)
+    }
+
+    #[test]
+    fn negate() {
+        assert_eq!(run("inp x\nmul x -1", &[7]).unwrap().get(Register::X), -7);
+    }
+
+    #[test]
+    fn three_times() {
+        let program = "inp z\ninp x\nmul z 3\neql z x";
+        assert_eq!(run(program, &[3, 9]).unwrap().get(Register::Z), 1);
+        assert_eq!(run(program, &[3, 8]).unwrap().get(Register::Z), 0);
+    }
+
+    #[test]
+    fn binary() {
+        let program = "inp w
+add z w
+mod z 2
+div w 2
+add y w
+mod y 2
+div w 2
+add x w
+mod x 2
+div w 2
+mod w 2";
+        assert_eq!(run(program, &[11]).unwrap().registers, [1, 0, 1, 1]);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(run("inp w\ninp x", &[1]), Err(AluError::MissingInput(1)));
+        assert_eq!(run("div w 0", &[]), Err(AluError::DivisionByZero(0)));
+        assert_eq!(
+            run("add w -3\nmod w 2", &[]),
+            Err(AluError::InvalidModulo(1))
+        );
+        assert_eq!(run("mod w -2", &[]), Err(AluError::InvalidModulo(0)));
+        let squaring = "inp z\n".to_string() + &"mul z z\n".repeat(7);
+        assert_eq!(run(squaring.trim_end(), &[9]), Err(AluError::Overflow(5)));
+        assert_eq!(
+            run("inp x\nadd x 1", &[i64::MAX]),
+            Err(AluError::Overflow(1))
+        );
+        assert_eq!(
+            run("inp x\nadd x -1\ninp y\ndiv x y", &[i64::MIN + 1, -1]),
+            Err(AluError::Overflow(3))
+        );
+    }
+
+    #[test]
+    fn display() {
+        let source = "inp w\nadd x -12\nmul y z\ndiv z 26\nmod x 26\neql x w";
+        let (_, program) = parse_program(source).unwrap();
+        let shown: Vec<String> = program.iter().map(|i| i.to_string()).collect();
+        assert_eq!(shown.join("\n"), source);
+    }
+}
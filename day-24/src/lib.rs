@@ -0,0 +1,242 @@
+//! Day 24: Arithmetic Logic Unit
+
+use nom::IResult;
+
+pub mod alu;
+
+use alu::{Alu, Instruction, Operand, Register};
+
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(24);
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
+pub fn process_part1(input: &str) -> String {
+    let (_, program) = parse_input(input).unwrap();
+    let [largest, _] = model_numbers(&program).unwrap();
+    largest.to_string()
+}
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
+pub fn process_part2(input: &str) -> String {
+    let (_, program) = parse_input(input).unwrap();
+    let [_, smallest] = model_numbers(&program).unwrap();
+    smallest.to_string()
+}
+
+/// Number of digits in a model number, one MONAD block each
+pub const DIGITS: usize = 14;
+/// Number of instructions in a MONAD block
+pub const BLOCK: usize = 18;
+
+/// Constants that differ between the blocks of MONAD
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Block {
+    /// 1 for blocks that push onto the base 26 stack in z, 26 for blocks that pop
+    pub divisor: i64,
+    /// Added to the top of the stack before comparing it with the digit
+    pub check: i64,
+    /// Added to the digit before pushing it
+    pub offset: i64,
+}
+
+impl Block {
+    /// The instructions of a MONAD block with these constants
+    pub fn instructions(&self) -> Vec<Instruction> {
+        use Instruction::*;
+        use Operand::Number as N;
+        use Register::*;
+        let r = Operand::Register;
+        vec![
+            Inp(W),
+            Mul(X, N(0)),
+            Add(X, r(Z)),
+            Mod(X, N(26)),
+            Div(Z, N(self.divisor)),
+            Add(X, N(self.check)),
+            Eql(X, r(W)),
+            Eql(X, N(0)),
+            Mul(Y, N(0)),
+            Add(Y, N(25)),
+            Mul(Y, r(X)),
+            Add(Y, N(1)),
+            Mul(Z, r(Y)),
+            Mul(Y, N(0)),
+            Add(Y, r(W)),
+            Add(Y, N(self.offset)),
+            Mul(Y, r(X)),
+            Add(Z, r(Y)),
+        ]
+    }
+}
+
+/// Splits MONAD into its blocks, failing if any block differs from the expected shape
+pub fn analyze(program: &[Instruction]) -> Result<Vec<Block>, String> {
+    if program.len() != DIGITS * BLOCK {
+        return Err(format!(
+            "expected {} instructions, got {}",
+            DIGITS * BLOCK,
+            program.len()
+        ));
+    }
+    program
+        .chunks(BLOCK)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let number = |index: usize| match chunk[index] {
+                Instruction::Div(_, Operand::Number(n))
+                | Instruction::Add(_, Operand::Number(n)) => Some(n),
+                _ => None,
+            };
+            let block = Block {
+                divisor: number(4).unwrap_or_default(),
+                check: number(5).unwrap_or_default(),
+                offset: number(15).unwrap_or_default(),
+            };
+            if block.instructions() != chunk || ![1, 26].contains(&block.divisor) {
+                return Err(format!("block {} isn't a MONAD block", i + 1));
+            }
+            Ok(block)
+        })
+        .collect()
+}
+
+/// Pairs every popping block with the block that pushed, as (push, pop, difference)
+/// so that an accepted model number has `digit[pop] == digit[push] + difference`
+pub fn constraints(blocks: &[Block]) -> Result<Vec<(usize, usize, i64)>, String> {
+    let mut stack = Vec::new();
+    let mut pairs = Vec::new();
+    for (i, block) in blocks.iter().enumerate() {
+        if block.divisor == 1 {
+            if block.check <= 9 {
+                return Err(format!(
+                    "block {} could match a digit instead of pushing",
+                    i + 1
+                ));
+            }
+            stack.push(i);
+        } else {
+            let push = stack
+                .pop()
+                .ok_or(format!("block {} pops from an empty stack", i + 1))?;
+            pairs.push((push, i, blocks[push].offset + block.check));
+        }
+    }
+    if !stack.is_empty() {
+        return Err("z is never empty at the end".to_string());
+    }
+    Ok(pairs)
+}
+
+/// Whether MONAD accepts `number`, by running it on the ALU
+pub fn accepts(program: &[Instruction], number: u64) -> bool {
+    let digits: Vec<i64> = number
+        .to_string()
+        .chars()
+        .map(|c| c.to_digit(10).unwrap() as i64)
+        .collect();
+    if digits.contains(&0) {
+        return false;
+    }
+    let mut alu = Alu::new();
+    alu.run(program, digits).is_ok() && alu.get(Register::Z) == 0
+}
+
+/// The largest and smallest model numbers accepted by MONAD, both verified on the ALU
+pub fn model_numbers(program: &[Instruction]) -> Result<[u64; 2], String> {
+    let pairs = constraints(&analyze(program)?)?;
+    let mut largest = [0; DIGITS];
+    let mut smallest = [0; DIGITS];
+    for (push, pop, difference) in pairs {
+        if difference.abs() > 8 {
+            return Err(format!(
+                "digits {} and {} can't differ by {}",
+                push + 1,
+                pop + 1,
+                difference
+            ));
+        }
+        largest[push] = 9.min(9 - difference);
+        largest[pop] = largest[push] + difference;
+        smallest[push] = 1.max(1 - difference);
+        smallest[pop] = smallest[push] + difference;
+    }
+    let number = |digits: [i64; DIGITS]| digits.iter().fold(0, |n, &d| n * 10 + d as u64);
+    let numbers = [number(largest), number(smallest)];
+    #[cfg(feature = "trace")]
+    tracing::debug!(largest = numbers[0], smallest = numbers[1], "model numbers");
+    for n in numbers {
+        if !accepts(program, n) {
+            return Err(format!("MONAD rejects {}", n));
+        }
+    }
+    Ok(numbers)
+}
+
+/// Parses the ALU program
+pub fn parse_input(input: &str) -> IResult<&str, Vec<Instruction>> {
+    alu::parse_program(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// MONAD with (divisor, check, offset) per block, pairing digits 1-14, 2-5, 3-4, 6-7, 8-11, 9-10 and 12-13
+    fn monad() -> String {
+        [
+            (1, 11, 1),
+            (1, 12, 2),
+            (1, 13, 3),
+            (26, -3, 0),
+            (26, -5, 0),
+            (1, 14, 0),
+            (26, 2, 0),
+            (1, 10, 7),
+            (1, 15, 4),
+            (26, -4, 0),
+            (26, -9, 0),
+            (1, 12, 5),
+            (26, -1, 0),
+            (26, -2, 0),
+        ]
+        .iter()
+        .flat_map(|&(divisor, check, offset)| {
+            Block {
+                divisor,
+                check,
+                offset,
+            }
+            .instructions()
+        })
+        .map(|instruction| instruction.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+    }
+
+    #[test]
+    fn part1() {
+        let result = process_part1(&monad());
+        assert_eq!(result, "99996799997598");
+    }
+
+    #[test]
+    fn part2() {
+        let result = process_part2(&monad());
+        assert_eq!(result, "24111133111151");
+    }
+
+    #[test]
+    fn verified() {
+        let (_, program) = parse_input(&monad()).unwrap();
+        assert!(accepts(&program, 99996799997598));
+        assert!(!accepts(&program, 99996799997599));
+        assert!(!accepts(&program, 99996709997598));
+        assert!(analyze(&program[1..]).is_err());
+        let mut changed = program.clone();
+        changed[20] = Instruction::Add(Register::Y, Operand::Number(1));
+        assert_eq!(
+            analyze(&changed),
+            Err("block 2 isn't a MONAD block".to_string())
+        );
+    }
+}
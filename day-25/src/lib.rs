@@ -0,0 +1,266 @@
+//! Day 25: Sea Cucumber
+
+use std::fmt::Display;
+
+use aoc_parse::lines_of;
+use nom::{character::complete::one_of, multi::many1, IResult};
+
+#[cfg(feature = "plugin")]
+aoc_plugin::export_day!(25);
+
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
+pub fn process_part1(input: &str) -> String {
+    let (_, mut herds) = parse_input(input).unwrap();
+    herds.settle().to_string()
+}
+
+/// There's no second puzzle on the last day
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, ret))]
+pub fn process_part2(_input: &str) -> String {
+    "Merry Christmas!".to_string()
+}
+
+/// A row of the grid as a bit per location, the first location in the lowest bit
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Row {
+    words: Vec<u64>,
+}
+
+impl Row {
+    fn new(width: usize) -> Self {
+        Self {
+            words: vec![0; width.div_ceil(64)],
+        }
+    }
+
+    fn get(&self, x: usize) -> bool {
+        self.words[x / 64] >> (x % 64) & 1 == 1
+    }
+
+    fn set(&mut self, x: usize) {
+        self.words[x / 64] |= 1 << (x % 64);
+    }
+
+    fn zip(&self, other: &Row, f: impl Fn(u64, u64) -> u64) -> Row {
+        let words = self.words.iter().zip(other.words.iter());
+        Row {
+            words: words.map(|(&a, &b)| f(a, b)).collect(),
+        }
+    }
+
+    fn count(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// Every bit moved one location east, the last one wrapping around to the first
+    fn east(&self, width: usize) -> Row {
+        let mut row = Row::new(width);
+        let mut carry = self.get(width - 1) as u64;
+        for (word, &old) in row.words.iter_mut().zip(self.words.iter()) {
+            *word = old << 1 | carry;
+            carry = old >> 63;
+        }
+        row.clear_tail(width);
+        row
+    }
+
+    /// Every bit moved one location west, the first one wrapping around to the last
+    fn west(&self, width: usize) -> Row {
+        let mut row = Row::new(width);
+        // the first bit wraps around into the last word
+        let mut carry = (self.get(0) as u64) << ((width - 1) % 64);
+        for (word, &old) in row.words.iter_mut().zip(self.words.iter()).rev() {
+            *word = old >> 1 | carry;
+            carry = old << 63;
+        }
+        row
+    }
+
+    /// Clears the bits past `width` in the last word
+    fn clear_tail(&mut self, width: usize) {
+        if !width.is_multiple_of(64) {
+            let last = self.words.len() - 1;
+            self.words[last] &= (1 << (width % 64)) - 1;
+        }
+    }
+}
+
+/// Both herds on the wrapping sea floor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Herds {
+    pub width: usize,
+    pub height: usize,
+    east: Vec<Row>,
+    south: Vec<Row>,
+    /// Number of steps taken so far
+    pub steps: usize,
+}
+
+impl Herds {
+    /// Grid from rows of `.`, `>` and `v`
+    pub fn new(rows: &[Vec<char>]) -> Self {
+        let width = rows.first().map_or(0, |row| row.len());
+        assert!(
+            rows.iter().all(|row| row.len() == width),
+            "Should be a rectangle!"
+        );
+        let mut east = vec![Row::new(width); rows.len()];
+        let mut south = vec![Row::new(width); rows.len()];
+        for (y, row) in rows.iter().enumerate() {
+            for (x, &c) in row.iter().enumerate() {
+                match c {
+                    '>' => east[y].set(x),
+                    'v' => south[y].set(x),
+                    _ => {}
+                }
+            }
+        }
+        Self {
+            width,
+            height: rows.len(),
+            east,
+            south,
+            steps: 0,
+        }
+    }
+
+    fn occupied(&self, y: usize) -> Row {
+        self.east[y].zip(&self.south[y], |e, s| e | s)
+    }
+
+    /// Moves the east-facing herd, then the south-facing one, returns how many moved
+    pub fn step(&mut self) -> usize {
+        let mut moved = 0;
+
+        for y in 0..self.height {
+            let arriving = self.east[y]
+                .east(self.width)
+                .zip(&self.occupied(y), |e, o| e & !o);
+            let leaving = arriving.west(self.width);
+            moved += arriving.count();
+            self.east[y] = self.east[y]
+                .zip(&leaving, |e, l| e & !l)
+                .zip(&arriving, |e, a| e | a);
+        }
+
+        let occupied: Vec<Row> = (0..self.height).map(|y| self.occupied(y)).collect();
+        let leaving: Vec<Row> = (0..self.height)
+            .map(|y| {
+                let below = &occupied[(y + 1) % self.height];
+                self.south[y].zip(below, |s, b| s & !b)
+            })
+            .collect();
+        for (y, leaving) in leaving.iter().enumerate() {
+            if leaving.is_empty() {
+                continue;
+            }
+            moved += leaving.count();
+            let below = (y + 1) % self.height;
+            self.south[y] = self.south[y].zip(leaving, |s, l| s & !l);
+            self.south[below] = self.south[below].zip(leaving, |s, l| s | l);
+        }
+
+        self.steps += 1;
+        #[cfg(feature = "trace")]
+        tracing::trace!(step = self.steps, moved, "step");
+        moved
+    }
+
+    /// Steps until nothing moves, returns the number of that step
+    pub fn settle(&mut self) -> usize {
+        while self.step() > 0 {}
+        self.steps
+    }
+}
+
+impl Display for Herds {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let c = if self.east[y].get(x) {
+                    '>'
+                } else if self.south[y].get(x) {
+                    'v'
+                } else {
+                    '.'
+                };
+                write!(f, "{}", c)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses the grid of sea cucumbers
+pub fn parse_input(input: &str) -> IResult<&str, Herds> {
+    let (input, rows) = lines_of(many1(one_of(".>v")))(input)?;
+    Ok((input, Herds::new(&rows)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn herds(input: &str) -> Herds {
+        parse_input(input).unwrap().1
+    }
+
+    #[test]
+    fn part1() {
+        let input = "v...>>.vv>
+.vv>>.vv..
+>>.>v>...v
+>>v>>.>.v.
+v>v.vv.v..
+>.>>..v...
+.vv..>.>v.
+v.v..>>v.v
+....v..v.>";
+        let result = process_part1(input);
+        assert_eq!(result, "58");
+    }
+
+    #[test]
+    fn single_row() {
+        let mut herds = herds("...>>>>>...");
+        herds.step();
+        assert_eq!(herds.to_string(), "...>>>>.>..\n");
+        herds.step();
+        assert_eq!(herds.to_string(), "...>>>.>.>.\n");
+    }
+
+    #[test]
+    fn wrapping() {
+        let mut herds = herds("..........\n.>v....v..\n.......>..\n..........");
+        herds.step();
+        assert_eq!(
+            herds.to_string(),
+            "..........\n.>........\n..v....v>.\n..........\n"
+        );
+        let mut herds = self::herds("...>\nv...\n....");
+        herds.step();
+        assert_eq!(herds.to_string(), ">...\n....\nv...\n");
+    }
+
+    #[test]
+    fn wide_rows() {
+        // a row spanning three words, with the herd wrapping around its end
+        let mut row = vec!['.'; 150];
+        row[149] = '>';
+        row[63] = '>';
+        row[64] = '>';
+        let mut herds = Herds::new(&[row]);
+        herds.step();
+        let shown = herds.to_string();
+        let east: Vec<usize> = shown.match_indices('>').map(|(i, _)| i).collect();
+        assert_eq!(east, vec![0, 63, 65]);
+    }
+}